@@ -0,0 +1,260 @@
+//! A lossless concrete syntax tree.
+//!
+//! Unlike the AST, the concrete syntax tree (CST) retains every token of the
+//! input, including whitespace and comments, along with the exact source text
+//! of each token. Converting a CST back to a `String` produces the input it was
+//! created from, making it useful for tools that modify source code (e.g. to
+//! rename a symbol): such tools can change the tokens of specific nodes, while
+//! the rest of the input is left as-is.
+//!
+//! The nodes of a CST correspond to the top-level expressions of a module. Any
+//! tokens that come before, after or in between these expressions (e.g. the
+//! comments and empty lines between two methods) are stored directly in the
+//! tree.
+use crate::lexer::{Lexer, TokenKind};
+use crate::nodes::{Module, Node, TopLevelExpression};
+use crate::source_location::SourceLocation;
+use std::fmt;
+
+/// A token in a concrete syntax tree.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CstToken {
+    pub kind: TokenKind,
+
+    /// The exact source text of the token.
+    ///
+    /// For tokens such as strings this text may differ from the value produced
+    /// by the lexer, as escape sequences aren't replaced.
+    pub text: String,
+
+    pub location: SourceLocation,
+}
+
+impl fmt::Display for CstToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// The kind of top-level expression a node is created for.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CstNodeKind {
+    DefineConstant,
+    DefineMethod,
+    DefineClass,
+    DefineTrait,
+    ReopenClass,
+    ImplementTrait,
+    Import,
+    ExternImport,
+}
+
+impl CstNodeKind {
+    fn new(expression: &TopLevelExpression) -> Self {
+        match expression {
+            TopLevelExpression::DefineConstant(_) => Self::DefineConstant,
+            TopLevelExpression::DefineMethod(_) => Self::DefineMethod,
+            TopLevelExpression::DefineClass(_) => Self::DefineClass,
+            TopLevelExpression::DefineTrait(_) => Self::DefineTrait,
+            TopLevelExpression::ReopenClass(_) => Self::ReopenClass,
+            TopLevelExpression::ImplementTrait(_) => Self::ImplementTrait,
+            TopLevelExpression::Import(_) => Self::Import,
+            TopLevelExpression::ExternImport(_) => Self::ExternImport,
+        }
+    }
+}
+
+/// A node in a concrete syntax tree, containing the tokens of a single
+/// top-level expression.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CstNode {
+    pub kind: CstNodeKind,
+    pub tokens: Vec<CstToken>,
+    pub location: SourceLocation,
+}
+
+impl fmt::Display for CstNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in &self.tokens {
+            token.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A value stored directly in a concrete syntax tree.
+#[derive(Debug, PartialEq, Eq)]
+pub enum CstElement {
+    Token(CstToken),
+    Node(CstNode),
+}
+
+impl fmt::Display for CstElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CstElement::Token(token) => token.fmt(f),
+            CstElement::Node(node) => node.fmt(f),
+        }
+    }
+}
+
+/// A concrete syntax tree of a module.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cst {
+    pub elements: Vec<CstElement>,
+}
+
+impl Cst {
+    /// Builds a CST using the tokens produced by `lexer`, and the module
+    /// produced by parsing the same input.
+    pub(crate) fn new(mut lexer: Lexer, module: &Module) -> Self {
+        let mut elements = Vec::new();
+        let mut exprs = module.expressions.iter().peekable();
+        let mut node: Option<CstNode> = None;
+
+        loop {
+            let (token, mut text) = lexer.next_token_with_source();
+
+            // The lexer skips a shebang line as part of producing the first
+            // token, so we split it off into a separate token.
+            if text.starts_with("#!") {
+                let size = text.find('\n').map_or(text.len(), |i| i + 1);
+                let shebang: String = text.drain(0..size).collect();
+                let location =
+                    SourceLocation::new(1..=1, 1..=shebang.trim_end().len());
+
+                elements.push(CstElement::Token(CstToken {
+                    kind: TokenKind::Comment,
+                    text: shebang,
+                    location,
+                }));
+            }
+
+            if token.kind == TokenKind::Null {
+                break;
+            }
+
+            let token =
+                CstToken { kind: token.kind, text, location: token.location };
+            let start = token.location.line_column();
+
+            while let Some(expr) = exprs.peek() {
+                let loc = expr.location();
+
+                if start <= (*loc.line_range.end(), *loc.column_range.end()) {
+                    break;
+                }
+
+                if let Some(node) = node.take() {
+                    elements.push(CstElement::Node(node));
+                }
+
+                exprs.next();
+            }
+
+            let Some(expr) = exprs.peek() else {
+                elements.push(CstElement::Token(token));
+                continue;
+            };
+
+            if start < expr.location().line_column() {
+                elements.push(CstElement::Token(token));
+                continue;
+            }
+
+            node.get_or_insert_with(|| CstNode {
+                kind: CstNodeKind::new(expr),
+                tokens: Vec::new(),
+                location: expr.location().clone(),
+            })
+            .tokens
+            .push(token);
+        }
+
+        if let Some(node) = node {
+            elements.push(CstElement::Node(node));
+        }
+
+        Cst { elements }
+    }
+
+    /// Returns an iterator over the nodes in `self`.
+    pub fn nodes(&self) -> impl Iterator<Item = &CstNode> {
+        self.elements.iter().filter_map(|element| match element {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+}
+
+impl fmt::Display for Cst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for element in &self.elements {
+            element.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn parse(input: &str) -> Cst {
+        Parser::new(input.into(), "test.inko".into()).parse_cst().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = [
+            "",
+            "\n\n",
+            "# A comment",
+            "#!/usr/bin/env inko\nimport std.stdio\n",
+            "#!/usr/bin/env inko",
+            "import std.stdio.(STDOUT)\n\n# Docs\nfn main {\n  10\n}\n",
+            "class A {\n  let @a: Int\n\n  fn a -> Int {\n    @a # foo\n  }\n}",
+            "fn a { 'a\\nb\\u{AC}' + \"c\\{10}d{\"e\"}\" }",
+            "fn a {\n  'foo \\\n    bar'\n}",
+            "let A = [10,  20,\n  30]\n\n\n",
+        ];
+
+        for input in inputs {
+            assert_eq!(parse(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_nodes() {
+        let cst = parse("# Docs\nimport std.stdio\n\nfn a {\n  10\n}\n");
+        let kinds: Vec<_> = cst.nodes().map(|n| n.kind).collect();
+
+        assert_eq!(kinds, vec![CstNodeKind::Import, CstNodeKind::DefineMethod]);
+        assert!(matches!(
+            &cst.elements[0],
+            CstElement::Token(CstToken { kind: TokenKind::Comment, .. })
+        ));
+        assert_eq!(
+            cst.nodes().map(|n| n.to_string()).collect::<Vec<_>>(),
+            vec!["import std.stdio", "fn a {\n  10\n}"]
+        );
+    }
+
+    #[test]
+    fn test_shebang() {
+        let cst = parse("#!/usr/bin/env inko\nfn a {}");
+
+        assert_eq!(
+            cst.elements[0],
+            CstElement::Token(CstToken {
+                kind: TokenKind::Comment,
+                text: "#!/usr/bin/env inko\n".to_string(),
+                location: SourceLocation::new(1..=1, 1..=19)
+            })
+        );
+        assert_eq!(cst.nodes().next().unwrap().to_string(), "fn a {}");
+    }
+}
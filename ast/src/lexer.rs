@@ -1,5 +1,8 @@
 //! Lexical analysis of Inko source code.
 use crate::source_location::SourceLocation;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::Arc;
 use unicode_segmentation::UnicodeSegmentation;
 
 const NULL: u8 = 0;
@@ -32,8 +35,10 @@ const GREATER: u8 = 62;
 const QUESTION: u8 = 63;
 const AT_SIGN: u8 = 64;
 const UPPER_A: u8 = 65;
+const UPPER_B: u8 = 66;
 const UPPER_E: u8 = 69;
 const UPPER_F: u8 = 70;
+const UPPER_O: u8 = 79;
 const UPPER_X: u8 = 88;
 const UPPER_Z: u8 = 90;
 const BRACKET_OPEN: u8 = 91;
@@ -42,9 +47,11 @@ const BRACKET_CLOSE: u8 = 93;
 const CARET: u8 = 94;
 const UNDERSCORE: u8 = 95;
 const LOWER_A: u8 = 97;
+const LOWER_B: u8 = 98;
 const LOWER_E: u8 = 101;
 const LOWER_F: u8 = 102;
 const LOWER_N: u8 = 110;
+const LOWER_O: u8 = 111;
 const LOWER_R: u8 = 114;
 const LOWER_T: u8 = 116;
 const LOWER_U: u8 = 117;
@@ -53,6 +60,7 @@ const LOWER_Z: u8 = 122;
 const CURLY_OPEN: u8 = 123;
 const PIPE: u8 = 124;
 const CURLY_CLOSE: u8 = 125;
+const TILDE: u8 = 126;
 
 /// The escape sequence literals supported by a single quoted string, and their
 /// replacement bytes.
@@ -79,6 +87,7 @@ pub enum TokenKind {
     And,
     Arrow,
     As,
+    AsSafe,
     Assign,
     Async,
     BitAnd,
@@ -92,6 +101,7 @@ pub enum TokenKind {
     Break,
     Builtin,
     Case,
+    Char,
     Class,
     Colon,
     Comma,
@@ -115,6 +125,7 @@ pub enum TokenKind {
     For,
     Ge,
     Gt,
+    Heredoc,
     Identifier,
     If,
     Implement,
@@ -167,6 +178,7 @@ pub enum TokenKind {
     Uni,
     UnicodeEscape,
     UnsignedShr,
+    UnterminatedHeredoc,
     UnsignedShrAssign,
     While,
     Whitespace,
@@ -181,6 +193,7 @@ impl TokenKind {
             TokenKind::And => "the 'and' keyword",
             TokenKind::Arrow => "a '->'",
             TokenKind::As => "the 'as' keyword",
+            TokenKind::AsSafe => "the 'as?' keyword",
             TokenKind::Assign => "a '='",
             TokenKind::Async => "the 'async' keyword",
             TokenKind::BitAnd => "a '&'",
@@ -193,6 +206,7 @@ impl TokenKind {
             TokenKind::BracketOpen => "an '['",
             TokenKind::Break => "the 'break' keyword",
             TokenKind::Class => "the 'class' keyword",
+            TokenKind::Char => "a character",
             TokenKind::Colon => "a ':'",
             TokenKind::Comma => "a ','",
             TokenKind::Comment => "a comment",
@@ -214,6 +228,7 @@ impl TokenKind {
             TokenKind::For => "the 'for' keyword",
             TokenKind::Gt => "a '>'",
             TokenKind::Ge => "a '>='",
+            TokenKind::Heredoc => "a heredoc",
             TokenKind::Identifier => "an identifier",
             TokenKind::If => "the 'if' keyword",
             TokenKind::Implement => "the 'impl' keyword",
@@ -249,6 +264,7 @@ impl TokenKind {
             TokenKind::ShrAssign => "a '>>='",
             TokenKind::UnsignedShr => "a '>>>'",
             TokenKind::UnsignedShrAssign => "a '>>>='",
+            TokenKind::UnterminatedHeredoc => "an unterminated heredoc",
             TokenKind::SingleStringClose => "a '''",
             TokenKind::SingleStringOpen => "a '''",
             TokenKind::Static => "the 'static' keyword",
@@ -279,7 +295,7 @@ impl TokenKind {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub value: String,
@@ -308,6 +324,7 @@ impl Token {
             self.kind,
             TokenKind::And
                 | TokenKind::As
+                | TokenKind::AsSafe
                 | TokenKind::Async
                 | TokenKind::Break
                 | TokenKind::Class
@@ -409,6 +426,7 @@ enum State {
 }
 
 /// A lexer for Inko source code.
+#[derive(Clone)]
 pub struct Lexer {
     /// The stream of bytes to process.
     input: Vec<u8>,
@@ -434,6 +452,10 @@ pub struct Lexer {
 
     /// The current (starting) column number.
     column: usize,
+
+    /// The file the input originates from, if this differs from the module
+    /// the input is parsed for.
+    origin: Option<Arc<PathBuf>>,
 }
 
 impl Lexer {
@@ -448,11 +470,36 @@ impl Lexer {
             states: vec![State::Default],
             line: 1,
             column: 1,
+            origin: None,
+        }
+    }
+
+    /// Sets the file to record as the origin of all source locations produced
+    /// by this lexer.
+    pub fn set_origin(&mut self, origin: PathBuf) {
+        self.origin = Some(Arc::new(origin));
+    }
+
+    /// Returns a lexer that starts lexing at the start of the given line.
+    ///
+    /// Lines start at 1. If the line is beyond the end of the input, the lexer
+    /// starts at the end of the input.
+    pub fn starting_at_line(input: Vec<u8>, line: usize) -> Self {
+        let mut lexer = Self::new(input);
+
+        while lexer.line < line && lexer.has_next() {
+            if lexer.current_byte() == NEWLINE {
+                lexer.advance_line();
+            } else {
+                lexer.position += 1;
+            }
         }
+
+        lexer
     }
 
     pub fn start_location(&self) -> SourceLocation {
-        SourceLocation::new(self.line..=self.line, self.column..=self.column)
+        self.location(self.line..=self.line, self.column..=self.column)
     }
 
     pub fn next_token(&mut self) -> Token {
@@ -467,12 +514,29 @@ impl Lexer {
         }
     }
 
+    /// Returns the next token, along with the exact source text consumed to
+    /// produce it.
+    ///
+    /// Unlike the value of a token, the text includes any characters skipped
+    /// while producing the token (e.g. escaped whitespace in a string), and
+    /// escape sequences aren't replaced. Concatenating the text of all tokens
+    /// thus produces the input as-is.
+    pub(crate) fn next_token_with_source(&mut self) -> (Token, String) {
+        let start = self.position.min(self.max_position);
+        let token = self.next_token();
+        let end = self.position.min(self.max_position);
+        let source =
+            String::from_utf8_lossy(&self.input[start..end]).into_owned();
+
+        (token, source)
+    }
+
     fn source_location(
         &self,
         start_line: usize,
         start_column: usize,
     ) -> SourceLocation {
-        SourceLocation::new(
+        self.location(
             start_line..=self.line,
             // The end column points to whatever comes _after_ the last
             // processed character. This means the end column is one column
@@ -481,6 +545,15 @@ impl Lexer {
         )
     }
 
+    fn location(
+        &self,
+        line_range: RangeInclusive<usize>,
+        column_range: RangeInclusive<usize>,
+    ) -> SourceLocation {
+        SourceLocation::new(line_range, column_range)
+            .with_origin(self.origin.clone())
+    }
+
     fn current_byte(&self) -> u8 {
         if self.has_next() {
             self.input[self.position]
@@ -601,6 +674,10 @@ impl Lexer {
         match self.current_byte() {
             ZERO..=NINE => self.number(false),
             AT_SIGN => self.field(),
+            HASH if self.position == 0 && self.next_byte() == EXCLAMATION => {
+                self.shebang();
+                self.next_token()
+            }
             HASH => self.comment(),
             CURLY_OPEN => self.curly_open(),
             CURLY_CLOSE => self.curly_close(),
@@ -625,6 +702,7 @@ impl Lexer {
             EXCLAMATION => self.exclamation(),
             DOT => self.dot(),
             COMMA => self.comma(),
+            QUESTION => self.character(),
             UNDERSCORE => self.underscore(),
             LOWER_A..=LOWER_Z => self.identifier_or_keyword(self.position),
             UPPER_A..=UPPER_Z => self.constant(self.position),
@@ -696,6 +774,22 @@ impl Lexer {
             return self.token(kind, start, line);
         }
 
+        // Octal and binary digits are validated when converting the literal to
+        // an integer, so e.g. `0b12` produces an error instead of two separate
+        // integers.
+        if first == ZERO
+            && matches!(second, LOWER_O | UPPER_O | LOWER_B | UPPER_B)
+        {
+            // Advance 2 for "0o" or "0b"
+            self.position += 2;
+
+            while let ZERO..=NINE | UNDERSCORE = self.current_byte() {
+                self.position += 1;
+            }
+
+            return self.token(kind, start, line);
+        }
+
         loop {
             match self.current_byte() {
                 ZERO..=NINE | UNDERSCORE => {}
@@ -760,6 +854,16 @@ impl Lexer {
         comment
     }
 
+    /// Skips a shebang line (e.g. `#!/usr/bin/env inko`) at the start of the
+    /// input, allowing scripts to be executed directly.
+    fn shebang(&mut self) {
+        while self.has_next() && self.current_byte() != NEWLINE {
+            self.position += 1;
+        }
+
+        self.advance_line();
+    }
+
     fn curly_open(&mut self) -> Token {
         self.curly_braces += 1;
 
@@ -801,6 +905,90 @@ impl Lexer {
         self.single_character_token(TokenKind::DoubleStringOpen)
     }
 
+    fn character(&mut self) -> Token {
+        let start = self.position;
+        let line = self.line;
+        let mut value = String::new();
+
+        // Skip the '?'.
+        self.position += 1;
+
+        match self.current_byte() {
+            BACKSLASH if self.next_byte() == LOWER_U => {
+                let escape_start = self.position;
+
+                while self.has_next() {
+                    match self.current_byte() {
+                        CURLY_CLOSE => {
+                            self.position += 1;
+                            break;
+                        }
+                        SPACE | TAB | CARRIAGE_RETURN | NEWLINE => break,
+                        _ => self.position += 1,
+                    }
+                }
+
+                let escape = &self.input[escape_start..self.position];
+                let parsed = escape
+                    .strip_prefix(b"\\u{")
+                    .and_then(|v| v.strip_suffix(b"}"))
+                    .filter(|v| !v.is_empty() && v.len() <= 6)
+                    .and_then(|v| std::str::from_utf8(v).ok())
+                    .and_then(|v| u32::from_str_radix(v, 16).ok())
+                    .and_then(char::from_u32);
+
+                match parsed {
+                    Some(chr) => value.push(chr),
+                    None => return self.invalid(start, self.position),
+                }
+            }
+            BACKSLASH => match DOUBLE_ESCAPES.get(self.next_byte()) {
+                Some(byte) => {
+                    value.push(byte as char);
+                    self.position += 2;
+                }
+                None => {
+                    let end = (self.position + 2).min(self.max_position);
+
+                    return self.invalid(start, end);
+                }
+            },
+            SPACE | TAB | CARRIAGE_RETURN | NEWLINE | NULL => {}
+            byte => {
+                let size = match byte {
+                    0xF0.. => 4,
+                    0xE0.. => 3,
+                    0xC0.. => 2,
+                    _ => 1,
+                };
+                let end = (self.position + size).min(self.max_position);
+
+                value.push_str(&self.slice_string(self.position, end));
+                self.position = end;
+            }
+        }
+
+        // Any trailing letters or digits are included, such that `?ab` is
+        // rejected as a literal with too many characters, instead of being
+        // parsed as `?a` followed by `b`.
+        let rest = self.position;
+
+        while let ZERO..=NINE
+        | LOWER_A..=LOWER_Z
+        | UPPER_A..=UPPER_Z
+        | UNDERSCORE = self.current_byte()
+        {
+            self.position += 1;
+        }
+
+        value.push_str(&self.slice_string(rest, self.position));
+
+        let mut token = self.token(TokenKind::Char, start, line);
+
+        token.value = value;
+        token
+    }
+
     fn colon(&mut self) -> Token {
         let start = self.position;
         let line = self.line;
@@ -879,6 +1067,13 @@ impl Lexer {
     }
 
     fn less(&mut self) -> Token {
+        if self.next_byte() == LESS
+            && self.peek(2) == TILDE
+            && self.peek(3).is_ascii_uppercase()
+        {
+            return self.heredoc();
+        }
+
         if self.next_byte() == LESS {
             return self.double_operator(TokenKind::Shl, TokenKind::ShlAssign);
         }
@@ -886,6 +1081,121 @@ impl Lexer {
         self.operator(TokenKind::Lt, TokenKind::Le, self.position)
     }
 
+    /// Lexes a heredoc such as `<<~END ... END` into a single token.
+    ///
+    /// The text starts on the line after the opening tag, and ends before the
+    /// first line that starts with the terminator, ignoring any leading
+    /// whitespace. The smallest indentation of the lines that aren't empty is
+    /// removed from every line, and every line (including the last one) ends
+    /// with a newline.
+    fn heredoc(&mut self) -> Token {
+        let start = self.position;
+        let line = self.line;
+        let column = self.column;
+
+        self.position += 3;
+
+        while matches!(
+            self.current_byte(),
+            UPPER_A..=UPPER_Z | ZERO..=NINE | UNDERSCORE
+        ) {
+            self.position += 1;
+        }
+
+        let name = self.input[start + 3..self.position].to_vec();
+
+        while matches!(self.current_byte(), SPACE | TAB | CARRIAGE_RETURN) {
+            self.position += 1;
+        }
+
+        if self.current_byte() != NEWLINE {
+            return self
+                .invalid(start, (self.position + 1).min(self.max_position));
+        }
+
+        let tag = self.slice_string(start, start + 3 + name.len());
+
+        self.advance_column(&tag);
+
+        let tag_location = self.source_location(line, column);
+        let mut lines = Vec::new();
+
+        self.advance_line();
+
+        loop {
+            let line_start = self.position;
+            let line_end = self.input[line_start..]
+                .iter()
+                .position(|&b| b == NEWLINE)
+                .map_or(self.max_position, |i| line_start + i);
+            let text = &self.input[line_start..line_end];
+            let indent =
+                text.iter().take_while(|&&b| b == SPACE || b == TAB).count();
+            let rest = &text[indent..];
+
+            if rest.starts_with(&name)
+                && !matches!(
+                    rest.get(name.len()).cloned(),
+                    Some(
+                        ZERO..=NINE
+                        | LOWER_A..=LOWER_Z
+                        | UPPER_A..=UPPER_Z
+                        | UNDERSCORE,
+                    )
+                )
+            {
+                self.position = line_start + indent + name.len();
+                self.column = indent + name.len() + 1;
+                break;
+            }
+
+            if line_end == self.max_position {
+                self.position = self.max_position;
+
+                return Token::new(
+                    TokenKind::UnterminatedHeredoc,
+                    String::from_utf8_lossy(&name).into_owned(),
+                    tag_location,
+                );
+            }
+
+            lines.push(line_start..line_end);
+            self.position = line_end;
+            self.advance_line();
+        }
+
+        let indent = lines
+            .iter()
+            .map(|range| &self.input[range.clone()])
+            .filter(|text| !text.iter().all(|&b| self.is_whitespace(b)))
+            .map(|text| {
+                text.iter().take_while(|&&b| b == SPACE || b == TAB).count()
+            })
+            .min()
+            .unwrap_or(0);
+        let mut buffer = Vec::new();
+
+        for range in lines {
+            let text = &self.input[range];
+            let strip = text
+                .iter()
+                .take(indent)
+                .take_while(|&&b| b == SPACE || b == TAB)
+                .count();
+
+            buffer.extend_from_slice(&text[strip..]);
+            buffer.push(NEWLINE);
+        }
+
+        let value = String::from_utf8_lossy(&buffer).into_owned();
+
+        Token::new(
+            TokenKind::Heredoc,
+            value,
+            self.source_location(line, column),
+        )
+    }
+
     fn greater(&mut self) -> Token {
         if self.next_byte() == GREATER {
             return if self.peek(2) == GREATER {
@@ -959,6 +1269,7 @@ impl Lexer {
             },
             3 => match value.as_str() {
                 "and" => TokenKind::And,
+                "as?" => TokenKind::AsSafe,
                 "for" => TokenKind::For,
                 "let" => TokenKind::Let,
                 "ref" => TokenKind::Ref,
@@ -1230,6 +1541,12 @@ impl Lexer {
                     self.position += 1;
                     break;
                 }
+                // This ensures `a!=b` is lexed as `a != b`, instead of `a!`
+                // followed by `=b`.
+                EXCLAMATION if self.next_byte() != EQUAL => {
+                    self.position += 1;
+                    break;
+                }
                 _ => break,
             }
         }
@@ -1345,11 +1662,11 @@ impl Lexer {
         // reported within the bounds of the column range.
         let lines = self.line..=self.line;
         let location = if self.column == 1 {
-            SourceLocation::new(lines, 1..=1)
+            self.location(lines, 1..=1)
         } else {
             let column = self.column - 1;
 
-            SourceLocation::new(lines, column..=column)
+            self.location(lines, column..=column)
         };
 
         Token::null(location)
@@ -1424,6 +1741,7 @@ mod tests {
     #[test]
     fn test_token_is_keyword() {
         assert!(tok(TokenKind::As, "", 1..=1, 1..=1).is_keyword());
+        assert!(tok(TokenKind::AsSafe, "", 1..=1, 1..=1).is_keyword());
         assert!(tok(TokenKind::Async, "", 1..=1, 1..=1).is_keyword());
         assert!(tok(TokenKind::Break, "", 1..=1, 1..=1).is_keyword());
         assert!(tok(TokenKind::Builtin, "", 1..=1, 1..=1).is_keyword());
@@ -1491,6 +1809,17 @@ mod tests {
         assert!(!tok1.same_line_as(&tok3));
     }
 
+    #[test]
+    fn test_lexer_starting_at_line() {
+        let mut lexer = Lexer::starting_at_line(Vec::from("10\n20\n30"), 2);
+
+        assert_eq!(lexer.next_token(), tok(Integer, "20", 2..=2, 1..=2));
+
+        let mut lexer = Lexer::starting_at_line(Vec::from("10"), 5);
+
+        assert_eq!(lexer.next_token().kind, Null);
+    }
+
     #[test]
     fn test_lexer_integer() {
         assert_token!("10", Integer, "10", 1..=1, 1..=2);
@@ -1499,6 +1828,11 @@ mod tests {
         assert_token!("0xaf", Integer, "0xaf", 1..=1, 1..=4);
         assert_token!("0xFF", Integer, "0xFF", 1..=1, 1..=4);
         assert_token!("0xF_F", Integer, "0xF_F", 1..=1, 1..=5);
+        assert_token!("0o755", Integer, "0o755", 1..=1, 1..=5);
+        assert_token!("0O7_5", Integer, "0O7_5", 1..=1, 1..=5);
+        assert_token!("0b1010", Integer, "0b1010", 1..=1, 1..=6);
+        assert_token!("0B1_0", Integer, "0B1_0", 1..=1, 1..=5);
+        assert_token!("0b12", Integer, "0b12", 1..=1, 1..=4);
         assert_token!("10Ea", Integer, "10", 1..=1, 1..=2);
         assert_token!("10.+5", Integer, "10", 1..=1, 1..=2);
     }
@@ -1524,6 +1858,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lexer_character() {
+        assert_token!("?a", Char, "a", 1..=1, 1..=2);
+        assert_token!("?)", Char, ")", 1..=1, 1..=2);
+        assert_token!("?a)", Char, "a", 1..=1, 1..=2);
+        assert_token!("?é", Char, "é", 1..=1, 1..=2);
+        assert_token!("?\\n", Char, "\n", 1..=1, 1..=3);
+        assert_token!("?\\t", Char, "\t", 1..=1, 1..=3);
+        assert_token!("?\\\\", Char, "\\", 1..=1, 1..=3);
+        assert_token!("?\\u{1F600}", Char, "\u{1F600}", 1..=1, 1..=10);
+        assert_token!("?ab", Char, "ab", 1..=1, 1..=3);
+        assert_token!("?", Char, "", 1..=1, 1..=1);
+        assert_token!("? ", Char, "", 1..=1, 1..=1);
+        assert_token!("?\\q", Invalid, "?\\q", 1..=1, 1..=3);
+        assert_token!("?\\u{XYZ}", Invalid, "?\\u{XYZ}", 1..=1, 1..=8);
+        assert_token!("?\\u{1F600", Invalid, "?\\u{1F600", 1..=1, 1..=9);
+    }
+
     #[test]
     fn test_lexer_field() {
         assert_token!("@foo", Field, "foo", 1..=1, 1..=4);
@@ -1542,6 +1894,27 @@ mod tests {
         assert_token!("# €€€", Comment, "€€€", 1..=1, 1..=5);
     }
 
+    #[test]
+    fn test_lexer_shebang() {
+        assert_token!(
+            "#!/usr/bin/env inko\nfoo",
+            Identifier,
+            "foo",
+            2..=2,
+            1..=3
+        );
+        assert_token!(
+            "#!/usr/bin/env inko\n# foo",
+            Comment,
+            "foo",
+            2..=2,
+            1..=5
+        );
+        assert_token!("#!/usr/bin/env inko", Null, "", 2..=2, 1..=1);
+        assert_token!("#!foo", Null, "", 2..=2, 1..=1);
+        assert_token!(" #!foo", Whitespace, " ", 1..=1, 1..=1);
+    }
+
     #[test]
     fn test_lexer_curly_braces() {
         assert_token!("{", CurlyOpen, "{", 1..=1, 1..=1);
@@ -1678,6 +2051,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lexer_heredoc() {
+        assert_tokens!(
+            "<<~END\n  foo\n    bar\n\n  baz\n  END",
+            tok(Heredoc, "foo\n  bar\n\nbaz\n", 1..=6, 1..=5)
+        );
+        assert_tokens!("<<~END\nEND", tok(Heredoc, "", 1..=2, 1..=3));
+        assert_tokens!(
+            "<<~END \r\n\tfoo\r\n\tEND",
+            tok(Heredoc, "foo\r\n", 1..=3, 1..=4)
+        );
+        assert_tokens!(
+            "<<~END_1\nEND\nEND_1",
+            tok(Heredoc, "END\n", 1..=3, 1..=5)
+        );
+        assert_tokens!(
+            "<<~END\n a\n END.b",
+            tok(Heredoc, "a\n", 1..=3, 1..=4),
+            tok(Dot, ".", 3..=3, 5..=5),
+            tok(Identifier, "b", 3..=3, 6..=6)
+        );
+        assert_tokens!(
+            "a <<~B",
+            tok(Identifier, "a", 1..=1, 1..=1),
+            tok(Whitespace, " ", 1..=1, 2..=2),
+            tok(Invalid, "<<~B", 1..=1, 3..=6)
+        );
+        assert_tokens!(
+            "<<~END\nfoo",
+            tok(UnterminatedHeredoc, "END", 1..=1, 1..=6)
+        );
+        assert_tokens!(
+            "a << ~",
+            tok(Identifier, "a", 1..=1, 1..=1),
+            tok(Whitespace, " ", 1..=1, 2..=2),
+            tok(Shl, "<<", 1..=1, 3..=4),
+            tok(Whitespace, " ", 1..=1, 5..=5),
+            tok(Invalid, "~", 1..=1, 6..=6)
+        );
+    }
+
     #[test]
     fn test_lexer_double_quoted_string() {
         assert_tokens!(
@@ -2031,6 +2445,59 @@ mod tests {
         assert_token!(".", Dot, ".", 1..=1, 1..=1);
     }
 
+    #[test]
+    fn test_lexer_consecutive_dots() {
+        assert_tokens!(
+            "..",
+            tok(Dot, ".", 1..=1, 1..=1),
+            tok(Dot, ".", 1..=1, 2..=2)
+        );
+        assert_tokens!(
+            "...",
+            tok(Dot, ".", 1..=1, 1..=1),
+            tok(Dot, ".", 1..=1, 2..=2),
+            tok(Dot, ".", 1..=1, 3..=3)
+        );
+        assert_tokens!(
+            "....",
+            tok(Dot, ".", 1..=1, 1..=1),
+            tok(Dot, ".", 1..=1, 2..=2),
+            tok(Dot, ".", 1..=1, 3..=3),
+            tok(Dot, ".", 1..=1, 4..=4)
+        );
+        assert_tokens!(
+            "1..5",
+            tok(Integer, "1", 1..=1, 1..=1),
+            tok(Dot, ".", 1..=1, 2..=2),
+            tok(Dot, ".", 1..=1, 3..=3),
+            tok(Integer, "5", 1..=1, 4..=4)
+        );
+        assert_tokens!(
+            "1...5",
+            tok(Integer, "1", 1..=1, 1..=1),
+            tok(Dot, ".", 1..=1, 2..=2),
+            tok(Dot, ".", 1..=1, 3..=3),
+            tok(Dot, ".", 1..=1, 4..=4),
+            tok(Integer, "5", 1..=1, 5..=5)
+        );
+        assert_tokens!(
+            "1 .. 5",
+            tok(Integer, "1", 1..=1, 1..=1),
+            tok(Whitespace, " ", 1..=1, 2..=2),
+            tok(Dot, ".", 1..=1, 3..=3),
+            tok(Dot, ".", 1..=1, 4..=4),
+            tok(Whitespace, " ", 1..=1, 5..=5),
+            tok(Integer, "5", 1..=1, 6..=6)
+        );
+        assert_tokens!(
+            "1.5..2.5",
+            tok(Float, "1.5", 1..=1, 1..=3),
+            tok(Dot, ".", 1..=1, 4..=4),
+            tok(Dot, ".", 1..=1, 5..=5),
+            tok(Float, "2.5", 1..=1, 6..=8)
+        );
+    }
+
     #[test]
     fn test_lexer_comma() {
         assert_token!(",", Comma, ",", 1..=1, 1..=1);
@@ -2039,6 +2506,7 @@ mod tests {
     #[test]
     fn test_lexer_keywords() {
         assert_token!("as", As, "as", 1..=1, 1..=2);
+        assert_token!("as?", AsSafe, "as?", 1..=1, 1..=3);
         assert_token!("fn", Fn, "fn", 1..=1, 1..=2);
         assert_token!("if", If, "if", 1..=1, 1..=2);
         assert_token!("or", Or, "or", 1..=1, 1..=2);
@@ -2099,6 +2567,7 @@ mod tests {
         assert_token!("_9", Identifier, "_9", 1..=1, 1..=2);
         assert_token!("__1", Identifier, "__1", 1..=1, 1..=3);
         assert_token!("foo?", Identifier, "foo?", 1..=1, 1..=4);
+        assert_token!("foo!", Identifier, "foo!", 1..=1, 1..=4);
     }
 
     #[test]
@@ -2144,4 +2613,49 @@ mod tests {
         assert_eq!(lexer.next_token(), tok(Identifier, "a?", 1..=1, 1..=2));
         assert_eq!(lexer.next_token(), tok(Identifier, "b", 1..=1, 3..=3));
     }
+
+    #[test]
+    fn test_lexer_identifier_with_exclamation_mark() {
+        let mut lexer = lexer("a!b");
+
+        assert_eq!(lexer.next_token(), tok(Identifier, "a!", 1..=1, 1..=2));
+        assert_eq!(lexer.next_token(), tok(Identifier, "b", 1..=1, 3..=3));
+    }
+
+    #[test]
+    fn test_lexer_identifier_followed_by_not_equal() {
+        assert_tokens!(
+            "a!=b",
+            tok(Identifier, "a", 1..=1, 1..=1),
+            tok(Ne, "!=", 1..=1, 2..=3),
+            tok(Identifier, "b", 1..=1, 4..=4)
+        );
+        assert_tokens!(
+            "a! != b",
+            tok(Identifier, "a!", 1..=1, 1..=2),
+            tok(Whitespace, " ", 1..=1, 3..=3),
+            tok(Ne, "!=", 1..=1, 4..=5),
+            tok(Whitespace, " ", 1..=1, 6..=6),
+            tok(Identifier, "b", 1..=1, 7..=7)
+        );
+    }
+
+    #[test]
+    fn test_lexer_with_origin() {
+        let mut lexer = lexer("a");
+        let origin = Arc::new(PathBuf::from("foo.inko"));
+
+        lexer.set_origin(PathBuf::from("foo.inko"));
+
+        let token = lexer.next_token();
+        let null = lexer.next_token();
+
+        assert_eq!(token.location.origin, Some(origin.clone()));
+        assert_eq!(null.location.origin, Some(origin.clone()));
+        assert_ne!(token.location, location(1..=1, 1..=1));
+        assert_eq!(
+            token.location,
+            location(1..=1, 1..=1).with_origin(Some(origin))
+        );
+    }
 }
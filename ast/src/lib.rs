@@ -1,4 +1,5 @@
 //! Inko's lexer, parser and AST.
+pub mod cst;
 pub mod lexer;
 pub mod nodes;
 pub mod parser;
@@ -3,12 +3,44 @@ use crate::source_location::SourceLocation;
 use std::cmp::{Eq, PartialEq};
 use std::path::PathBuf;
 
+/// The first step of an import path that is relative to the importing module.
+pub const SELF_STEP: &str = "self";
+
 pub trait Node {
     fn location(&self) -> &SourceLocation;
+
+    /// Returns the start line, start column, end line and end column of the
+    /// node.
+    ///
+    /// The end column is the column of the last character of the node on its
+    /// last line.
+    fn span(&self) -> (usize, usize, usize, usize) {
+        let loc = self.location();
+
+        (
+            *loc.line_range.start(),
+            *loc.column_range.start(),
+            *loc.line_range.end(),
+            *loc.column_range.end(),
+        )
+    }
+}
+
+/// A type that can be compared to another value of the same type while
+/// ignoring source locations.
+pub trait StructuralEq {
+    /// Returns `true` if `self` and `other` have the same structure, ignoring
+    /// the source locations of the nodes.
+    ///
+    /// This makes it possible to compare trees parsed from different sources,
+    /// such as `10 + 20` and `10  +  20`.
+    fn structural_eq(&self, other: &Self) -> bool;
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct IntLiteral {
+    /// The literal exactly as written in the source code, such as `0x1F` or
+    /// `1_000`.
     pub value: String,
     pub location: SourceLocation,
 }
@@ -27,6 +59,7 @@ impl Node for IntLiteral {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct FloatLiteral {
+    /// The literal exactly as written in the source code, such as `1.0e3`.
     pub value: String,
     pub location: SourceLocation,
 }
@@ -37,6 +70,19 @@ impl Node for FloatLiteral {
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct CharLiteral {
+    /// The character, with any escape sequences already replaced.
+    pub value: char,
+    pub location: SourceLocation,
+}
+
+impl Node for CharLiteral {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct StringText {
     pub value: String,
@@ -103,6 +149,33 @@ impl Node for Array {
     }
 }
 
+/// A value of an array literal whose values are to be included in the array,
+/// such as the `*other` in `[*other, 10]`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Splat {
+    pub value: Expression,
+    pub location: SourceLocation,
+}
+
+impl Node for Splat {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
+/// A set literal, such as `%[10, 20]`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Set {
+    pub values: Vec<Expression>,
+    pub location: SourceLocation,
+}
+
+impl Node for Set {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Tuple {
     pub values: Vec<Expression>,
@@ -314,10 +387,21 @@ impl Node for ImportSymbols {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ImportPath {
+    /// The steps of the path.
+    ///
+    /// For relative imports such as `import self.foo`, the first step is
+    /// `self`.
     pub steps: Vec<Identifier>,
     pub location: SourceLocation,
 }
 
+impl ImportPath {
+    /// Returns `true` if the path is relative to the importing module.
+    pub fn is_relative(&self) -> bool {
+        self.steps.first().map_or(false, |s| s.name == SELF_STEP)
+    }
+}
+
 impl Node for ImportPath {
     fn location(&self) -> &SourceLocation {
         &self.location
@@ -396,6 +480,8 @@ pub enum MethodKind {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DefineMethod {
+    /// The comments directly preceding the definition, if any.
+    pub documentation: Option<String>,
     pub public: bool,
     pub kind: MethodKind,
     pub operator: bool,
@@ -432,6 +518,31 @@ pub enum ClassExpression {
     DefineMethod(Box<DefineMethod>),
     DefineField(Box<DefineField>),
     DefineVariant(Box<DefineVariant>),
+    Section(Box<ClassSection>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SectionKind {
+    Public,
+    Static,
+}
+
+/// A group of class members that share the same modifier, such as
+/// `static { ... }`.
+///
+/// The parser applies the modifier to the members of the section, so the
+/// section itself only records how the members are grouped.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ClassSection {
+    pub kind: SectionKind,
+    pub body: ClassExpressions,
+    pub location: SourceLocation,
+}
+
+impl Node for ClassSection {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -457,6 +568,8 @@ pub enum ClassKind {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DefineClass {
+    /// The comments directly preceding the definition, if any.
+    pub documentation: Option<String>,
     pub public: bool,
     pub kind: ClassKind,
     pub name: Constant,
@@ -512,6 +625,8 @@ impl Node for TraitExpressions {
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DefineTrait {
+    /// The comments directly preceding the definition, if any.
+    pub documentation: Option<String>,
     pub public: bool,
     pub name: Constant,
     pub type_parameters: Option<TypeParameters>,
@@ -665,6 +780,7 @@ pub enum Expression {
     SingleString(Box<StringLiteral>),
     DoubleString(Box<DoubleStringLiteral>),
     Float(Box<FloatLiteral>),
+    Char(Box<CharLiteral>),
     Binary(Box<Binary>),
     Field(Box<Field>),
     Constant(Box<Constant>),
@@ -690,6 +806,8 @@ pub enum Expression {
     And(Box<And>),
     Or(Box<Or>),
     TypeCast(Box<TypeCast>),
+    Ascription(Box<Ascription>),
+    Splat(Box<Splat>),
     Throw(Box<Throw>),
     Return(Box<Return>),
     Try(Box<Try>),
@@ -703,6 +821,7 @@ pub enum Expression {
     ClassLiteral(Box<ClassLiteral>),
     Scope(Box<Scope>),
     Array(Box<Array>),
+    Set(Box<Set>),
     Tuple(Box<Tuple>),
 }
 
@@ -727,6 +846,7 @@ impl Node for Expression {
         match self {
             Expression::And(ref typ) => typ.location(),
             Expression::Array(ref typ) => typ.location(),
+            Expression::Set(ref typ) => typ.location(),
             Expression::AssignField(ref typ) => typ.location(),
             Expression::ReplaceField(ref typ) => typ.location(),
             Expression::AssignSetter(ref typ) => typ.location(),
@@ -738,6 +858,7 @@ impl Node for Expression {
             Expression::BinaryAssignVariable(ref typ) => typ.location(),
             Expression::Break(ref typ) => typ.location(),
             Expression::Call(ref typ) => typ.location(),
+            Expression::Char(ref typ) => typ.location(),
             Expression::ClassLiteral(ref typ) => typ.location(),
             Expression::Closure(ref typ) => typ.location(),
             Expression::Constant(ref typ) => typ.location(),
@@ -765,6 +886,8 @@ impl Node for Expression {
             Expression::Try(ref typ) => typ.location(),
             Expression::Tuple(ref typ) => typ.location(),
             Expression::TypeCast(ref typ) => typ.location(),
+            Expression::Ascription(ref typ) => typ.location(),
+            Expression::Splat(ref typ) => typ.location(),
             Expression::While(ref typ) => typ.location(),
             Expression::Mut(ref typ) => typ.location(),
             Expression::Recover(ref typ) => typ.location(),
@@ -1023,6 +1146,20 @@ pub enum OperatorKind {
     UnsignedShr,
 }
 
+impl OperatorKind {
+    /// Returns `true` if the operator is used for ordering two values, such as
+    /// `<` and `>=`.
+    pub fn is_ordering(&self) -> bool {
+        matches!(
+            self,
+            OperatorKind::Lt
+                | OperatorKind::Le
+                | OperatorKind::Gt
+                | OperatorKind::Ge
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Operator {
     pub kind: OperatorKind,
@@ -1086,9 +1223,27 @@ impl Node for BlockArguments {
     }
 }
 
+/// A list of variables explicitly captured by a closure.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Captures {
+    pub values: Vec<Identifier>,
+    pub location: SourceLocation,
+}
+
+impl Node for Captures {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Closure {
     pub moving: bool,
+
+    /// The variables captured by the closure.
+    ///
+    /// If this value is `None`, variables are captured implicitly.
+    pub captures: Option<Captures>,
     pub arguments: Option<BlockArguments>,
     pub return_type: Option<Type>,
     pub body: Expressions,
@@ -1284,6 +1439,10 @@ impl Node for Or {
 pub struct TypeCast {
     pub value: Expression,
     pub cast_to: Type,
+
+    /// If the cast is a safe cast (`as?`), producing a nil instead of an error
+    /// when the cast fails.
+    pub is_safe: bool,
     pub location: SourceLocation,
 }
 
@@ -1293,6 +1452,20 @@ impl Node for TypeCast {
     }
 }
 
+/// An expression with an explicit type, such as `10: Int`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ascription {
+    pub value: Expression,
+    pub target_type: Type,
+    pub location: SourceLocation,
+}
+
+impl Node for Ascription {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Throw {
     pub value: Expression,
@@ -1482,6 +1655,24 @@ impl Node for While {
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct Comment {
+    pub value: String,
+    pub location: SourceLocation,
+}
+
+impl From<Token> for Comment {
+    fn from(token: Token) -> Self {
+        Self { value: token.value, location: token.location }
+    }
+}
+
+impl Node for Comment {
+    fn location(&self) -> &SourceLocation {
+        &self.location
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Module {
     pub expressions: Vec<TopLevelExpression>,
@@ -1494,3 +1685,312 @@ impl Node for Module {
         &self.location
     }
 }
+
+/// Implements `StructuralEq` for types that don't contain any source locations,
+/// using their `PartialEq` implementation.
+macro_rules! structural_eq_using_eq {
+    ($($name: ty),* $(,)?) => {
+        $(
+            impl StructuralEq for $name {
+                fn structural_eq(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )*
+    };
+}
+
+/// Implements `StructuralEq` for structs by comparing each of their fields.
+///
+/// The fields are destructured, such that adding a field to a struct without
+/// listing it here results in a compile-time error.
+macro_rules! structural_eq_for_structs {
+    ($($name: ident { $($field: ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl StructuralEq for $name {
+                fn structural_eq(&self, other: &Self) -> bool {
+                    let $name { $($field),* } = self;
+
+                    $($field.structural_eq(&other.$field))&&*
+                }
+            }
+        )*
+    };
+}
+
+/// Implements `StructuralEq` for enums of which each variant wraps a single
+/// value.
+///
+/// As with structs, leaving out a variant results in a compile-time error.
+macro_rules! structural_eq_for_enums {
+    ($($name: ident { $($variant: ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl StructuralEq for $name {
+                fn structural_eq(&self, other: &Self) -> bool {
+                    match self {
+                        $(
+                            $name::$variant(a) => matches!(
+                                other,
+                                $name::$variant(b) if a.structural_eq(b)
+                            ),
+                        )*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl StructuralEq for SourceLocation {
+    fn structural_eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Box<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        (**self).structural_eq(other)
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Option<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.structural_eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Vec<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.iter().zip(other).all(|(a, b)| a.structural_eq(b))
+    }
+}
+
+structural_eq_using_eq!(
+    bool,
+    char,
+    String,
+    PathBuf,
+    MethodKind,
+    SectionKind,
+    ClassKind,
+    OperatorKind,
+);
+
+structural_eq_for_structs!(
+    IntLiteral { value, location },
+    FloatLiteral { value, location },
+    CharLiteral { value, location },
+    StringText { value, location },
+    StringLiteral { value, location },
+    StringExpression { value, location },
+    DoubleStringLiteral { values, location },
+    Array { values, location },
+    Splat { value, location },
+    Set { values, location },
+    Tuple { values, location },
+    Identifier { name, location },
+    Constant { source, name, location },
+    Call { receiver, name, arguments, location },
+    AssignVariable { variable, value, location },
+    ReplaceVariable { variable, value, location },
+    AssignField { field, value, location },
+    ReplaceField { field, value, location },
+    AssignSetter { receiver, name, value, location },
+    BinaryAssignVariable { operator, variable, value, location },
+    BinaryAssignField { operator, field, value, location },
+    BinaryAssignSetter { operator, receiver, name, value, location },
+    ImportAlias { name, location },
+    ImportSymbol { name, alias, location },
+    ImportSymbols { values, location },
+    ImportPath { steps, location },
+    BuildTags { values, location },
+    Import { path, symbols, location, tags, include },
+    ExternImportPath { path, location },
+    ExternImport { path, location },
+    DefineConstant { public, name, value, location },
+    DefineMethod {
+        documentation,
+        public,
+        kind,
+        operator,
+        name,
+        type_parameters,
+        arguments,
+        return_type,
+        body,
+        location,
+    },
+    DefineField { public, name, value_type, location },
+    ClassSection { kind, body, location },
+    ClassExpressions { values, location },
+    DefineClass {
+        documentation,
+        public,
+        kind,
+        name,
+        type_parameters,
+        body,
+        location,
+    },
+    DefineVariant { name, members, location },
+    AssignInstanceLiteralField { field, value, location },
+    ClassLiteral { class_name, fields, location },
+    TraitExpressions { values, location },
+    DefineTrait {
+        documentation,
+        public,
+        name,
+        type_parameters,
+        requirements,
+        body,
+        location,
+    },
+    ImplementationExpressions { values, location },
+    ReopenClass { class_name, body, location, bounds },
+    Requirements { values, location },
+    TypeBound { name, requirements, location },
+    TypeBounds { values, location },
+    ImplementTrait { trait_name, class_name, body, location, bounds },
+    Scope { body, location },
+    Types { values, location },
+    TypeNames { values, location },
+    TypeParameter { name, requirements, location },
+    TypeParameters { values, location },
+    MethodArgument { name, value_type, location },
+    NamedArgument { name, value, location },
+    Arguments { values, location },
+    MethodArguments { values, variadic, location },
+    TypeName { name, arguments, location },
+    ReferenceType { type_reference, location },
+    RcType { name, location },
+    ClosureType { arguments, return_type, location },
+    TupleType { values, location },
+    Expressions { values, location },
+    Operator { kind, location },
+    Binary { left, right, operator, location },
+    Field { name, location },
+    BlockArgument { name, value_type, location },
+    BlockArguments { values, location },
+    Captures { values, location },
+    Closure { moving, captures, arguments, return_type, body, location },
+    DefineElseBlock { body, location },
+    DefineVariable { mutable, name, value, value_type, location },
+    SelfObject { location },
+    True { location },
+    Nil { location },
+    False { location },
+    Next { location },
+    Break { location },
+    Group { value, location },
+    Ref { value, location },
+    Mut { value, location },
+    Recover { body, location },
+    RcRef { value, location },
+    And { left, right, location },
+    Or { left, right, location },
+    TypeCast { value, cast_to, is_safe, location },
+    Ascription { value, target_type, location },
+    Throw { value, location },
+    Return { value, location },
+    Try { expression, location },
+    IfCondition { condition, body, location },
+    If { if_true, else_if, else_body, location },
+    TuplePattern { values, location },
+    VariantPattern { name, values, location },
+    WildcardPattern { location },
+    IdentifierPattern { name, mutable, value_type, location },
+    FieldPattern { field, pattern, location },
+    ClassPattern { values, location },
+    OrPattern { patterns, location },
+    StringPattern { value, location },
+    MatchCase { pattern, guard, body, location },
+    Match { expression, cases, location },
+    Loop { body, location },
+    While { condition, body, location },
+    Comment { value, location },
+    Module { expressions, file, location },
+);
+
+structural_eq_for_enums!(
+    DoubleStringValue { Text, Expression },
+    ClassExpression { DefineMethod, DefineField, DefineVariant, Section },
+    TopLevelExpression {
+        DefineConstant,
+        DefineMethod,
+        DefineClass,
+        DefineTrait,
+        ReopenClass,
+        ImplementTrait,
+        Import,
+        ExternImport,
+    },
+    Requirement { Trait, Mutable },
+    Expression {
+        Int,
+        SingleString,
+        DoubleString,
+        Float,
+        Char,
+        Binary,
+        Field,
+        Constant,
+        Identifier,
+        Call,
+        AssignVariable,
+        ReplaceVariable,
+        AssignField,
+        ReplaceField,
+        AssignSetter,
+        BinaryAssignVariable,
+        BinaryAssignField,
+        BinaryAssignSetter,
+        Closure,
+        DefineVariable,
+        SelfObject,
+        Group,
+        Next,
+        Break,
+        Ref,
+        Mut,
+        Recover,
+        And,
+        Or,
+        TypeCast,
+        Ascription,
+        Splat,
+        Throw,
+        Return,
+        Try,
+        If,
+        Match,
+        Loop,
+        While,
+        True,
+        False,
+        Nil,
+        ClassLiteral,
+        Scope,
+        Array,
+        Set,
+        Tuple,
+    },
+    Argument { Positional, Named },
+    ReferrableType { Named, Closure, Tuple },
+    Type { Named, Ref, Mut, Uni, Closure, Tuple },
+    Pattern {
+        Constant,
+        Variant,
+        Class,
+        Expression,
+        Identifier,
+        Tuple,
+        Wildcard,
+        Or,
+        String,
+    },
+);
@@ -4,6 +4,10 @@
 //! possible, it's not a lossless parser. This means that while you can
 //! reconstruct Inko source code from the AST, it may not be exactly the same as
 //! the input.
+//!
+//! If you need a lossless representation of the input, use
+//! `Parser::parse_cst` instead.
+use crate::cst::Cst;
 use crate::lexer::{Lexer, Token, TokenKind};
 use crate::nodes::*;
 use crate::source_location::SourceLocation;
@@ -40,6 +44,15 @@ macro_rules! location {
     };
 }
 
+/// The identifier that starts a block as an alternative to `{`.
+///
+/// `do` and `end` aren't keywords, so existing code using them as names (e.g.
+/// a variable called `end`) continues to work.
+const DO_BLOCK_START: &str = "do";
+
+/// The identifier that closes a block started using `do`.
+const DO_BLOCK_END: &str = "end";
+
 /// An error produced when encountering invalid syntax.
 #[derive(Debug)]
 pub struct ParseError {
@@ -64,19 +77,252 @@ pub struct Parser {
     ///
     /// When this value is 0, trailing blocks are allowed.
     trailing_block_allowed: u16,
+
+    /// The comments encountered while parsing, if they are to be retained.
+    comments: Option<Vec<Comment>>,
+
+    /// The syntax errors encountered while parsing, if the parser is to
+    /// recover from them.
+    errors: Option<Vec<ParseError>>,
+
+    /// The lines of the comments that may document the next definition.
+    doc_comments: Vec<String>,
+
+    /// The line of the last comment in `doc_comments`, or of the last token
+    /// that isn't a comment.
+    last_line: usize,
+
+    /// The line and column of the last token produced by the lexer, along
+    /// with the comments that directly precede it.
+    documentation: Option<(usize, usize, String)>,
+
+    /// The number of comma separated brackets (e.g. parentheses and argument
+    /// lists) we're nested in.
+    ///
+    /// When this value is greater than 0, newlines are insignificant. This
+    /// means that for example the arguments of a call may start on the line
+    /// after the call's name. Blocks reset this value to 0, as newlines are
+    /// significant when separating the expressions in a block. Array and set
+    /// literals also reset this value, as their values don't need to be
+    /// separated by commas, meaning a newline may separate two values.
+    brackets: u16,
 }
 
 impl Parser {
     pub fn new(input: Vec<u8>, file: PathBuf) -> Self {
         let lexer = Lexer::new(input);
 
-        Self { file, lexer, peeked: None, trailing_block_allowed: 0 }
+        Self {
+            file,
+            lexer,
+            peeked: None,
+            trailing_block_allowed: 0,
+            comments: None,
+            errors: None,
+            doc_comments: Vec::new(),
+            last_line: 0,
+            documentation: None,
+            brackets: 0,
+        }
+    }
+
+    /// Returns a parser for source code that originates from the file
+    /// `origin`, but is parsed as part of the module defined in `file`.
+    ///
+    /// The source locations of all nodes record `origin` as the file they
+    /// originate from, allowing diagnostics to point to the correct file.
+    pub fn with_origin(input: Vec<u8>, file: PathBuf, origin: PathBuf) -> Self {
+        let mut parser = Self::new(input, file);
+
+        parser.lexer.set_origin(origin);
+        parser
+    }
+
+    /// Parses the input into a module, and returns it along with all the
+    /// comments found in the input.
+    ///
+    /// The comments are returned in the order they appear in the input, and
+    /// aren't included in the module itself.
+    pub fn parse_with_comments(
+        &mut self,
+    ) -> Result<(Module, Vec<Comment>), ParseError> {
+        self.comments = Some(Vec::new());
+
+        let module = self.parse();
+        let comments = self.comments.take().unwrap_or_default();
+
+        module.map(|module| (module, comments))
+    }
+
+    /// Parses the input into a module, recovering from syntax errors.
+    ///
+    /// Instead of stopping at the first syntax error, the parser skips to the
+    /// next expression that starts on a new line and continues parsing from
+    /// there. If any errors are encountered, all of them are returned in the
+    /// order they're found.
+    ///
+    /// Recovering from errors is done on a best-effort basis, meaning an error
+    /// may result in additional errors that wouldn't be produced once the
+    /// first error is fixed.
+    pub fn parse_with_recovery(&mut self) -> Result<Module, Vec<ParseError>> {
+        self.errors = Some(Vec::new());
+
+        let module = self.parse();
+        let mut errors = self.errors.take().unwrap_or_default();
+
+        match module {
+            Ok(module) if errors.is_empty() => Ok(module),
+            Ok(_) => Err(errors),
+            Err(error) => {
+                errors.push(error);
+                Err(errors)
+            }
+        }
+    }
+
+    /// Parses the input into a lossless concrete syntax tree.
+    ///
+    /// The input is first parsed into a module, meaning syntax errors are
+    /// reported the same way as when using `Parser::parse`. Unlike the module,
+    /// the returned tree retains all tokens of the input, including whitespace
+    /// and comments.
+    pub fn parse_cst(&mut self) -> Result<Cst, ParseError> {
+        let lexer = self.lexer.clone();
+        let module = self.parse()?;
+
+        Ok(Cst::new(lexer, &module))
+    }
+
+    /// Parses the input into a module, reusing the top-level expressions of a
+    /// previously parsed module that come before the first changed line.
+    ///
+    /// The `previous` argument is the module produced by parsing the input as
+    /// it was before it was changed, and `line` is the first line (starting at
+    /// 1) that differs between the old and new input.
+    ///
+    /// Top-level expressions that end before the changed line are reused as-is,
+    /// and parsing resumes at the start of the line of the first top-level
+    /// expression that isn't reused, or at the start of the comments directly
+    /// above it such that its documentation is retained. Expressions that come
+    /// after the changed line are parsed again, as their source locations may
+    /// have changed.
+    pub fn reparse(
+        input: Vec<u8>,
+        file: PathBuf,
+        previous: Module,
+        line: usize,
+    ) -> Result<Module, ParseError> {
+        let start_loc = SourceLocation::new(1..=1, 1..=1);
+        let mut expressions = previous.expressions;
+        let mut resume_at = line;
+
+        while let Some(expr) = expressions.last() {
+            let loc = expr.location();
+
+            if *loc.line_range.end() < resume_at {
+                break;
+            }
+
+            resume_at = resume_at.min(*loc.line_range.start());
+            expressions.pop();
+        }
+
+        let reused_until = expressions
+            .last()
+            .map_or(0, |expr| *expr.location().line_range.end());
+        let lines: Vec<&[u8]> = input.split(|&byte| byte == b'\n').collect();
+
+        while resume_at > reused_until + 1
+            && lines.get(resume_at - 2).map_or(false, |line| {
+                line.iter().find(|byte| !byte.is_ascii_whitespace())
+                    == Some(&b'#')
+            })
+        {
+            resume_at -= 1;
+        }
+
+        let mut parser = Self::new(Vec::new(), file);
+
+        parser.lexer = Lexer::starting_at_line(input, resume_at);
+        parser.parse_expressions(start_loc, expressions)
     }
 
     pub fn parse(&mut self) -> Result<Module, ParseError> {
         let start_loc = self.lexer.start_location();
-        let mut expressions = Vec::new();
 
+        self.parse_expressions(start_loc, Vec::new())
+    }
+
+    /// Checks if all parentheses, brackets and curly braces in the input are
+    /// balanced.
+    ///
+    /// This only looks at the tokens in the input and doesn't parse them,
+    /// making it a cheap way of detecting missing or superfluous delimiters
+    /// before parsing the input. The error points to the delimiter that isn't
+    /// matched, instead of the end of the input.
+    pub fn check_balanced(&self) -> Result<(), ParseError> {
+        let mut lexer = self.lexer.clone();
+        let mut token =
+            self.peeked.clone().unwrap_or_else(|| lexer.next_token());
+        let mut open: Vec<Token> = Vec::new();
+
+        loop {
+            let (opening, opening_value) = match token.kind {
+                TokenKind::Null => break,
+                TokenKind::ParenOpen
+                | TokenKind::BracketOpen
+                | TokenKind::CurlyOpen => {
+                    open.push(token);
+                    token = lexer.next_token();
+                    continue;
+                }
+                TokenKind::ParenClose => (TokenKind::ParenOpen, "("),
+                TokenKind::BracketClose => (TokenKind::BracketOpen, "["),
+                TokenKind::CurlyClose => (TokenKind::CurlyOpen, "{"),
+                _ => {
+                    token = lexer.next_token();
+                    continue;
+                }
+            };
+
+            match open.pop() {
+                Some(start) if start.kind == opening => {}
+                Some(start) => {
+                    error!(
+                        token.location,
+                        "Expected the '{}' on line {}, column {} to be \
+                        closed, found '{}' instead",
+                        start.value,
+                        start.location.line_range.start(),
+                        start.location.column_range.start(),
+                        token.value
+                    );
+                }
+                None => {
+                    error!(
+                        token.location,
+                        "This '{}' doesn't have a matching opening '{}'",
+                        token.value,
+                        opening_value
+                    );
+                }
+            }
+
+            token = lexer.next_token();
+        }
+
+        if let Some(start) = open.pop() {
+            error!(start.location, "This '{}' is never closed", start.value);
+        }
+
+        Ok(())
+    }
+
+    fn parse_expressions(
+        &mut self,
+        start_loc: SourceLocation,
+        mut expressions: Vec<TopLevelExpression>,
+    ) -> Result<Module, ParseError> {
         loop {
             let token = self.next();
 
@@ -88,7 +334,10 @@ impl Parser {
                 return Ok(Module { expressions, file, location });
             }
 
-            expressions.push(self.top_level_expression(token)?);
+            match self.top_level_expression(token) {
+                Ok(expr) => expressions.push(expr),
+                Err(error) => self.recover(error, false)?,
+            }
         }
     }
 
@@ -104,6 +353,10 @@ impl Parser {
             TokenKind::Fn => self.define_module_method(start)?,
             TokenKind::Let => self.define_constant(start)?,
             _ => {
+                if let Some(err) = self.misplaced_pub(&start) {
+                    return Err(err);
+                }
+
                 error!(
                     start.location,
                     "Expected a top-level expression, found '{}' instead",
@@ -152,6 +405,13 @@ impl Parser {
                 error!(token.location, "Expected an identifier or keyword");
             }
 
+            if token.kind == TokenKind::SelfObject && !steps.is_empty() {
+                error!(
+                    token.location,
+                    "'self' is only valid at the start of an import path"
+                );
+            }
+
             steps.push(Identifier::from(token));
 
             if self.peek().kind != TokenKind::Dot {
@@ -171,8 +431,17 @@ impl Parser {
         let start_loc = steps.first().map(|s| &s.location).unwrap();
         let end_loc = steps.last().map(|s| &s.location).unwrap();
         let location = SourceLocation::start_end(start_loc, end_loc);
+        let path = ImportPath { steps, location };
+
+        if path.is_relative() && path.steps.len() == 1 {
+            error!(
+                path.location,
+                "Relative imports must include a module name, such as \
+                'import self.foo'"
+            );
+        }
 
-        Ok(ImportPath { steps, location })
+        Ok(path)
     }
 
     fn import_symbols(&mut self) -> Result<Option<ImportSymbols>, ParseError> {
@@ -375,6 +644,10 @@ impl Parser {
             TokenKind::False => self.false_literal(start),
             TokenKind::SingleStringOpen => self.single_string_literal(start)?,
             TokenKind::DoubleStringOpen => self.double_string_literal(start)?,
+            TokenKind::Heredoc => self.heredoc(start),
+            TokenKind::UnterminatedHeredoc => {
+                self.unterminated_heredoc(start)?
+            }
             TokenKind::Constant => self.constant_ref(start),
             TokenKind::ParenOpen => self.const_group(start)?,
             TokenKind::BracketOpen => self.const_array(start)?,
@@ -742,8 +1015,17 @@ impl Parser {
             if allow_variadic && token.kind == TokenKind::Dot {
                 self.expect(TokenKind::Dot)?;
                 self.expect(TokenKind::Dot)?;
-                token = self.expect(TokenKind::ParenClose)?;
+                token = self.require()?;
                 variadic = true;
+
+                if token.kind != TokenKind::ParenClose {
+                    error!(
+                        token.location,
+                        "The '...' argument must be the last argument, \
+                        found '{}' after it",
+                        token.value
+                    );
+                }
             }
 
             if token.kind == TokenKind::ParenClose {
@@ -759,7 +1041,15 @@ impl Parser {
                 }));
             }
 
-            values.push(self.define_method_argument(token)?);
+            let arg = self.define_method_argument(token)?;
+
+            if let Some(prev) =
+                values.iter().find(|v| v.name.name == arg.name.name)
+            {
+                Self::duplicate_argument(&arg.name, prev.name.location())?;
+            }
+
+            values.push(arg);
 
             if !values.is_empty() && self.peek().kind != TokenKind::ParenClose {
                 self.expect(TokenKind::Comma)?;
@@ -787,6 +1077,26 @@ impl Parser {
         Ok(MethodArgument { name, value_type, location })
     }
 
+    fn optional_closure_captures(
+        &mut self,
+    ) -> Result<Option<Captures>, ParseError> {
+        if self.peek().kind != TokenKind::BracketOpen {
+            return Ok(None);
+        }
+
+        let (values, location) = self.list(
+            TokenKind::BracketOpen,
+            TokenKind::BracketClose,
+            |parser, token| {
+                parser.require_token_kind(&token, TokenKind::Identifier)?;
+
+                Ok(Identifier::from(token))
+            },
+        )?;
+
+        Ok(Some(Captures { values, location }))
+    }
+
     fn optional_closure_arguments(
         &mut self,
     ) -> Result<Option<BlockArguments>, ParseError> {
@@ -844,6 +1154,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<TopLevelExpression, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let mut allow_variadic = false;
         let kind = match self.peek().kind {
@@ -867,9 +1178,7 @@ impl Parser {
         let body = if let MethodKind::Extern = kind {
             None
         } else {
-            let token = self.expect(TokenKind::CurlyOpen)?;
-
-            Some(self.expressions(token)?)
+            Some(self.method_body()?)
         };
 
         let location = SourceLocation::start_end(
@@ -881,6 +1190,7 @@ impl Parser {
         );
 
         Ok(TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+            documentation,
             public,
             operator,
             name,
@@ -897,6 +1207,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<DefineMethod, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let kind = match self.peek().kind {
             TokenKind::Async => {
@@ -928,12 +1239,12 @@ impl Parser {
         let type_parameters = self.optional_type_parameter_definitions()?;
         let arguments = self.optional_method_arguments(false)?;
         let return_type = self.optional_return_type()?;
-        let body_token = self.expect(TokenKind::CurlyOpen)?;
-        let body = self.expressions(body_token)?;
+        let body = self.method_body()?;
         let location =
             SourceLocation::start_end(&start.location, &body.location);
 
         Ok(DefineMethod {
+            documentation,
             public,
             operator,
             name,
@@ -950,6 +1261,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<DefineMethod, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let kind = match self.peek().kind {
             TokenKind::Move => {
@@ -967,12 +1279,12 @@ impl Parser {
         let type_parameters = self.optional_type_parameter_definitions()?;
         let arguments = self.optional_method_arguments(false)?;
         let return_type = self.optional_return_type()?;
-        let body_token = self.expect(TokenKind::CurlyOpen)?;
-        let body = self.expressions(body_token)?;
+        let body = self.method_body()?;
         let location =
             SourceLocation::start_end(&start.location, &body.location);
 
         Ok(DefineMethod {
+            documentation,
             public,
             operator,
             name,
@@ -1001,7 +1313,17 @@ impl Parser {
             let mut name = start.value;
             let mut location = start.location;
 
-            if self.peek().kind == TokenKind::Assign {
+            // A `=` directly following the name (e.g. `fn foo=`) defines a
+            // setter, while `fn foo = ...` defines a method with a single
+            // expression as its body.
+            let next = self.peek();
+            let setter = next.kind == TokenKind::Assign
+                && next.location.line_range.start()
+                    == location.line_range.start()
+                && *next.location.column_range.start()
+                    == location.column_range.end() + 1;
+
+            if setter {
                 let assign = self.next();
 
                 name.push('=');
@@ -1024,6 +1346,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<TopLevelExpression, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let kind = match self.peek().kind {
             TokenKind::Async => {
@@ -1062,6 +1385,7 @@ impl Parser {
             SourceLocation::start_end(&start.location, &body.location);
 
         Ok(TopLevelExpression::DefineClass(Box::new(DefineClass {
+            documentation,
             public,
             kind,
             name,
@@ -1096,13 +1420,13 @@ impl Parser {
     }
 
     fn class_expressions(&mut self) -> Result<ClassExpressions, ParseError> {
-        let start = self.expect(TokenKind::CurlyOpen)?;
+        let start = self.block_start()?;
         let mut values = Vec::new();
 
         loop {
             let token = self.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
+            if self.block_end(&start, &token)? {
                 let location =
                     SourceLocation::start_end(&start.location, &token.location);
 
@@ -1116,13 +1440,13 @@ impl Parser {
     fn extern_class_expressions(
         &mut self,
     ) -> Result<ClassExpressions, ParseError> {
-        let start = self.expect(TokenKind::CurlyOpen)?;
+        let start = self.block_start()?;
         let mut values = Vec::new();
 
         loop {
             let token = self.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
+            if self.block_end(&start, &token)? {
                 let location =
                     SourceLocation::start_end(&start.location, &token.location);
 
@@ -1159,7 +1483,16 @@ impl Parser {
             TokenKind::Case => ClassExpression::DefineVariant(Box::new(
                 self.define_variant(start)?,
             )),
+            TokenKind::Static | TokenKind::Pub
+                if self.next_is_block_start() =>
+            {
+                ClassExpression::Section(Box::new(self.class_section(start)?))
+            }
             _ => {
+                if let Some(err) = self.misplaced_pub(&start) {
+                    return Err(err);
+                }
+
                 error!(
                     start.location,
                     "Expected 'fn', 'let' or 'case', found '{}' instead",
@@ -1171,6 +1504,71 @@ impl Parser {
         Ok(expr)
     }
 
+    fn class_section(
+        &mut self,
+        start: Token,
+    ) -> Result<ClassSection, ParseError> {
+        let kind = if start.kind == TokenKind::Static {
+            SectionKind::Static
+        } else {
+            SectionKind::Public
+        };
+        let mut body = self.class_expressions()?;
+
+        Self::apply_section(&kind, &mut body.values)?;
+
+        let location =
+            SourceLocation::start_end(&start.location, &body.location);
+
+        Ok(ClassSection { kind, body, location })
+    }
+
+    /// Applies the modifier of a section to the members defined in it.
+    fn apply_section(
+        kind: &SectionKind,
+        values: &mut [ClassExpression],
+    ) -> Result<(), ParseError> {
+        for value in values {
+            match value {
+                ClassExpression::DefineMethod(node) => match kind {
+                    SectionKind::Public => node.public = true,
+                    SectionKind::Static => match node.kind {
+                        MethodKind::Instance | MethodKind::Static => {
+                            node.kind = MethodKind::Static;
+                        }
+                        _ => {
+                            error!(
+                                node.name.location.clone(),
+                                "Methods in a 'static' section can't be \
+                                async, moving or mutable"
+                            );
+                        }
+                    },
+                },
+                ClassExpression::DefineField(node) => match kind {
+                    SectionKind::Public => node.public = true,
+                    SectionKind::Static => {
+                        error!(
+                            node.location.clone(),
+                            "Fields can't be defined in a 'static' section"
+                        );
+                    }
+                },
+                ClassExpression::DefineVariant(node) => {
+                    error!(
+                        node.location.clone(),
+                        "Enum cases can't be defined in a section"
+                    );
+                }
+                ClassExpression::Section(node) => {
+                    Self::apply_section(kind, &mut node.body.values)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn define_field(
         &mut self,
         start: Token,
@@ -1325,13 +1723,13 @@ impl Parser {
     fn reopen_class_expressions(
         &mut self,
     ) -> Result<ImplementationExpressions, ParseError> {
-        let start = self.expect(TokenKind::CurlyOpen)?;
+        let start = self.block_start()?;
         let mut values = Vec::new();
 
         loop {
             let token = self.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
+            if self.block_end(&start, &token)? {
                 let location =
                     SourceLocation::start_end(&start.location, &token.location);
 
@@ -1354,13 +1752,13 @@ impl Parser {
     fn trait_implementation_expressions(
         &mut self,
     ) -> Result<ImplementationExpressions, ParseError> {
-        let start = self.expect(TokenKind::CurlyOpen)?;
+        let start = self.block_start()?;
         let mut values = Vec::new();
 
         loop {
             let token = self.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
+            if self.block_end(&start, &token)? {
                 let location =
                     SourceLocation::start_end(&start.location, &token.location);
 
@@ -1384,6 +1782,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<TopLevelExpression, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let name = Constant::from(self.expect(TokenKind::Constant)?);
         let type_parameters = self.optional_type_parameter_definitions()?;
@@ -1393,6 +1792,7 @@ impl Parser {
             SourceLocation::start_end(&start.location, &body.location);
 
         Ok(TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+            documentation,
             public,
             name,
             type_parameters,
@@ -1403,13 +1803,13 @@ impl Parser {
     }
 
     fn trait_expressions(&mut self) -> Result<TraitExpressions, ParseError> {
-        let start = self.expect(TokenKind::CurlyOpen)?;
+        let start = self.block_start()?;
         let mut values = Vec::new();
 
         loop {
             let token = self.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
+            if self.block_end(&start, &token)? {
                 let location =
                     SourceLocation::start_end(&start.location, &token.location);
 
@@ -1431,6 +1831,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<DefineMethod, ParseError> {
+        let documentation = self.documentation(&start);
         let public = self.next_is_public();
         let kind = match self.peek().kind {
             TokenKind::Move => {
@@ -1448,10 +1849,10 @@ impl Parser {
         let type_parameters = self.optional_type_parameter_definitions()?;
         let arguments = self.optional_method_arguments(false)?;
         let return_type = self.optional_return_type()?;
-        let body = if self.peek().kind == TokenKind::CurlyOpen {
-            let body_token = self.expect(TokenKind::CurlyOpen)?;
-
-            Some(self.expressions(body_token)?)
+        let body = if self.next_is_block_start()
+            || self.peek().kind == TokenKind::Assign
+        {
+            Some(self.method_body()?)
         } else {
             None
         };
@@ -1463,6 +1864,7 @@ impl Parser {
         let location = SourceLocation::start_end(&start.location, end_loc);
 
         Ok(DefineMethod {
+            documentation,
             public,
             operator,
             name,
@@ -1475,21 +1877,60 @@ impl Parser {
         })
     }
 
+    /// Parses the body of a method.
+    ///
+    /// The body is either a block, or a single expression following a `=`
+    /// (e.g. `fn double(value: Int) -> Int = value * 2`).
+    fn method_body(&mut self) -> Result<Expressions, ParseError> {
+        if self.peek().kind != TokenKind::Assign {
+            let start = self.block_start()?;
+
+            return self.expressions(start);
+        }
+
+        let assign = self.next();
+        let token = self.next();
+
+        if let TokenKind::Null | TokenKind::CurlyClose = token.kind {
+            error!(
+                assign.location,
+                "Expected an expression after the '=', found {} instead",
+                token.kind.description()
+            );
+        }
+
+        let expr = self.expression(token)?;
+        let location = expr.location().clone();
+
+        Ok(Expressions { values: vec![expr], location })
+    }
+
     fn expressions(&mut self, start: Token) -> Result<Expressions, ParseError> {
-        let mut values = Vec::new();
+        self.outside_brackets(|parser| {
+            let mut values = Vec::new();
 
-        loop {
-            let token = self.require()?;
+            loop {
+                let token = parser.require()?;
 
-            if token.kind == TokenKind::CurlyClose {
-                let location =
-                    SourceLocation::start_end(&start.location, &token.location);
+                if parser.block_end(&start, &token)? {
+                    let location = SourceLocation::start_end(
+                        &start.location,
+                        &token.location,
+                    );
 
-                return Ok(Expressions { values, location });
-            }
+                    return Ok(Expressions { values, location });
+                }
 
-            values.push(self.expression(token)?);
-        }
+                match parser.expression(token) {
+                    Ok(expr) => values.push(expr),
+                    Err(error) => {
+                        let do_block = start.kind == TokenKind::Identifier;
+
+                        parser.recover(error, do_block)?
+                    }
+                }
+            }
+        })
     }
 
     fn expressions_with_optional_curly_braces(
@@ -1557,8 +1998,23 @@ impl Parser {
 
         loop {
             if let Some(op) = self.binary_operator() {
+                if let Expression::Binary(ref lhs) = node {
+                    if lhs.operator.kind.is_ordering() && op.kind.is_ordering()
+                    {
+                        error!(
+                            op.location,
+                            "Comparison operators can't be chained, \
+                            use 'a < b and b < c' instead of 'a < b < c'"
+                        );
+                    }
+                }
+
                 let rhs_token = self.require()?;
-                let rhs = self.postfix(rhs_token)?;
+                let rhs = if op.kind == OperatorKind::Pow {
+                    self.power(rhs_token)?
+                } else {
+                    self.postfix(rhs_token)?
+                };
                 let location =
                     SourceLocation::start_end(node.location(), rhs.location());
 
@@ -1568,8 +2024,8 @@ impl Parser {
                     right: rhs,
                     location,
                 }));
-            } else if self.peek().kind == TokenKind::As {
-                self.next();
+            } else if let TokenKind::As | TokenKind::AsSafe = self.peek().kind {
+                let is_safe = self.next().kind == TokenKind::AsSafe;
 
                 let cast_token = self.require()?;
                 let cast_to = self.type_reference(cast_token)?;
@@ -1581,6 +2037,24 @@ impl Parser {
                 node = Expression::TypeCast(Box::new(TypeCast {
                     value: node,
                     cast_to,
+                    is_safe,
+                    location,
+                }));
+            } else if self.peek().kind == TokenKind::Colon {
+                // Named arguments are handled before parsing their values, so
+                // a `:` following an expression is always a type ascription.
+                self.next();
+
+                let type_token = self.require()?;
+                let target_type = self.type_reference(type_token)?;
+                let location = SourceLocation::start_end(
+                    node.location(),
+                    target_type.location(),
+                );
+
+                node = Expression::Ascription(Box::new(Ascription {
+                    value: node,
+                    target_type,
                     location,
                 }));
             } else {
@@ -1591,6 +2065,33 @@ impl Parser {
         Ok(node)
     }
 
+    /// Parses the right-hand side of a `**` operator.
+    ///
+    /// Unlike the other binary operators, `**` is right-associative, such that
+    /// `2 ** 3 ** 2` is parsed as `2 ** (3 ** 2)`.
+    fn power(&mut self, start: Token) -> Result<Expression, ParseError> {
+        let lhs = self.postfix(start)?;
+
+        if self.peek().kind != TokenKind::Pow {
+            return Ok(lhs);
+        }
+
+        let op_token = self.next();
+        let operator =
+            Operator { kind: OperatorKind::Pow, location: op_token.location };
+        let rhs_token = self.require()?;
+        let rhs = self.power(rhs_token)?;
+        let location =
+            SourceLocation::start_end(lhs.location(), rhs.location());
+
+        Ok(Expression::Binary(Box::new(Binary {
+            operator,
+            left: lhs,
+            right: rhs,
+            location,
+        })))
+    }
+
     fn binary_operator(&mut self) -> Option<Operator> {
         let op_kind = match self.peek().kind {
             TokenKind::Add => OperatorKind::Add,
@@ -1623,6 +2124,13 @@ impl Parser {
         let mut node = self.value(start)?;
 
         loop {
+            // Whitespace (including newlines) is skipped when peeking, so the
+            // `.` of a call may also start a new line. This allows long chains
+            // to be wrapped as follows:
+            //
+            //     foo
+            //       .bar
+            //       .baz
             let peeked = self.peek();
 
             if let TokenKind::Dot = peeked.kind {
@@ -1640,7 +2148,11 @@ impl Parser {
         // value expressions.
         let value = match start.kind {
             TokenKind::BracketOpen => self.array_literal(start)?,
+            TokenKind::Mod if self.peek().kind == TokenKind::BracketOpen => {
+                self.set_literal(start)?
+            }
             TokenKind::Break => self.break_loop(start),
+            TokenKind::Char => self.char_literal(start)?,
             TokenKind::Constant => self.constant(start)?,
             TokenKind::CurlyOpen => self.scope(start)?,
             TokenKind::Fn => self.closure(start)?,
@@ -1648,6 +2160,7 @@ impl Parser {
             TokenKind::False => self.false_literal(start),
             TokenKind::Field => self.field(start)?,
             TokenKind::Float => self.float_literal(start),
+            TokenKind::Heredoc => self.heredoc(start),
             TokenKind::Identifier => self.identifier(start)?,
             TokenKind::If => self.if_expression(start)?,
             TokenKind::Integer => self.int_literal(start),
@@ -1667,6 +2180,9 @@ impl Parser {
             TokenKind::Try => self.try_expression(start)?,
             TokenKind::While => self.while_expression(start)?,
             TokenKind::Let => self.define_variable(start)?,
+            TokenKind::UnterminatedHeredoc => {
+                self.unterminated_heredoc(start)?
+            }
             _ => {
                 error!(start.location, "'{}' can't be used here", start.value)
             }
@@ -1682,6 +2198,33 @@ impl Parser {
         }))
     }
 
+    fn char_literal(&mut self, start: Token) -> Result<Expression, ParseError> {
+        let mut chars = start.value.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(value), None) => {
+                Ok(Expression::Char(Box::new(CharLiteral {
+                    value,
+                    location: start.location,
+                })))
+            }
+            (None, _) => {
+                error!(
+                    start.location,
+                    "Expected a character after the '?', found nothing"
+                )
+            }
+            _ => {
+                error!(
+                    start.location,
+                    "Character literals must contain exactly one character, \
+                    found '{}' instead",
+                    start.value
+                )
+            }
+        }
+    }
+
     fn float_literal(&mut self, start: Token) -> Expression {
         Expression::Float(Box::new(FloatLiteral {
             value: start.value,
@@ -1689,33 +2232,168 @@ impl Parser {
         }))
     }
 
-    fn single_string_literal(
-        &mut self,
-        start: Token,
-    ) -> Result<Expression, ParseError> {
-        let value = if self.peek().kind == TokenKind::StringText {
-            let value_start = self.next();
-
-            Some(self.string_text(value_start))
-        } else {
+    fn heredoc(&mut self, start: Token) -> Expression {
+        let value = if start.value.is_empty() {
             None
+        } else {
+            Some(StringText {
+                value: start.value,
+                location: start.location.clone(),
+            })
         };
 
-        let close = self.expect(TokenKind::SingleStringClose)?;
-        let location =
-            SourceLocation::start_end(&start.location, &close.location);
-        let string = StringLiteral { value, location };
+        Expression::SingleString(Box::new(StringLiteral {
+            value,
+            location: start.location,
+        }))
+    }
 
-        Ok(Expression::SingleString(Box::new(string)))
+    fn unterminated_heredoc(
+        &mut self,
+        start: Token,
+    ) -> Result<Expression, ParseError> {
+        error!(
+            start.location,
+            "This heredoc is missing its terminator, which should be a line \
+            containing just '{}'",
+            start.value
+        );
     }
 
-    fn double_string_literal(
+    fn single_string_literal(
         &mut self,
         start: Token,
     ) -> Result<Expression, ParseError> {
-        let mut values = Vec::new();
+        let mut string = self.single_string(start)?;
+
+        while let Some(token) =
+            self.adjacent_string(&string.location, TokenKind::SingleStringOpen)?
+        {
+            let next = self.single_string(token)?;
+
+            string.location =
+                SourceLocation::start_end(&string.location, &next.location);
+            string.value = match (string.value, next.value) {
+                (Some(a), Some(b)) => Some(StringText {
+                    value: a.value + &b.value,
+                    location: SourceLocation::start_end(
+                        &a.location,
+                        &b.location,
+                    ),
+                }),
+                (a, b) => a.or(b),
+            };
+        }
+
+        Ok(Expression::SingleString(Box::new(string)))
+    }
+
+    fn single_string(
+        &mut self,
+        start: Token,
+    ) -> Result<StringLiteral, ParseError> {
+        let value = if self.peek().kind == TokenKind::StringText {
+            let value_start = self.next();
+
+            Some(self.string_text(value_start))
+        } else {
+            None
+        };
+
+        if self.peek().kind == TokenKind::Null {
+            self.unterminated_string(&start)?;
+        }
+
+        let close = self.expect(TokenKind::SingleStringClose)?;
+        let location =
+            SourceLocation::start_end(&start.location, &close.location);
+
+        Ok(StringLiteral { value, location })
+    }
+
+    fn double_string_literal(
+        &mut self,
+        start: Token,
+    ) -> Result<Expression, ParseError> {
+        let mut string = self.double_string(start)?;
+
+        while let Some(token) =
+            self.adjacent_string(&string.location, TokenKind::DoubleStringOpen)?
+        {
+            let next = self.double_string(token)?;
+            let mut values = next.values;
+
+            string.location =
+                SourceLocation::start_end(&string.location, &next.location);
+
+            // Text at the end of the first literal and the start of the second
+            // literal is merged, such that `"a" "b"` is the same as `"ab"`.
+            if let (
+                Some(DoubleStringValue::Text(a)),
+                Some(DoubleStringValue::Text(b)),
+            ) = (string.values.last_mut(), values.first())
+            {
+                a.value += &b.value;
+                a.location =
+                    SourceLocation::start_end(&a.location, &b.location);
+                values.remove(0);
+            }
+
+            string.values.append(&mut values);
+        }
+
+        Ok(Expression::DoubleString(Box::new(string)))
+    }
+
+    /// Returns the opening token of a string literal that directly follows the
+    /// string literal at `location`, if there is any.
+    ///
+    /// Adjacent string literals are concatenated at parse time. The literals
+    /// must be on the same line, unless we're inside parentheses or an
+    /// argument list. Array and set literals don't count, as a newline
+    /// separates their values. Both literals
+    /// must use the same quotes, as single and double quoted strings support
+    /// different features (e.g. string interpolation).
+    fn adjacent_string(
+        &mut self,
+        location: &SourceLocation,
+        kind: TokenKind,
+    ) -> Result<Option<Token>, ParseError> {
+        let brackets = self.brackets > 0;
+        let peeked = self.peek();
+
+        if !matches!(
+            peeked.kind,
+            TokenKind::SingleStringOpen | TokenKind::DoubleStringOpen
+        ) || !(brackets
+            || peeked.location.line_range.start() == location.line_range.end())
+        {
+            return Ok(None);
+        }
+
+        let token = self.next();
+
+        if token.kind != kind {
+            error!(
+                token.location,
+                "Adjacent string literals must use the same quotes"
+            );
+        }
+
+        Ok(Some(token))
+    }
+
+    fn double_string(
+        &mut self,
+        start: Token,
+    ) -> Result<DoubleStringLiteral, ParseError> {
+        let mut values = Vec::new();
 
         loop {
+            if self.peek().kind == TokenKind::Null {
+                self.unterminated_string(&start)?;
+            }
+
             let token = self.require()?;
 
             match token.kind {
@@ -1725,9 +2403,7 @@ impl Parser {
                         &token.location,
                     );
 
-                    let string = DoubleStringLiteral { values, location };
-
-                    return Ok(Expression::DoubleString(Box::new(string)));
+                    return Ok(DoubleStringLiteral { values, location });
                 }
                 TokenKind::StringText | TokenKind::UnicodeEscape => {
                     values.push(DoubleStringValue::Text(Box::new(
@@ -1766,6 +2442,17 @@ impl Parser {
         }
     }
 
+    fn unterminated_string(&self, start: &Token) -> Result<(), ParseError> {
+        let (line, column) = start.location.line_column();
+
+        error!(
+            start.location.clone(),
+            "Unterminated string literal starting at line {}, column {}",
+            line,
+            column
+        );
+    }
+
     fn string_text(&mut self, start: Token) -> StringText {
         let mut value = start.value;
         let mut end_loc = start.location.clone();
@@ -1789,27 +2476,63 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<Expression, ParseError> {
-        let mut values = Vec::new();
+        let (values, end) = self.array_values()?;
+        let location = SourceLocation::start_end(&start.location, &end);
 
-        loop {
-            let token = self.require()?;
+        Ok(Expression::Array(Box::new(Array { values, location })))
+    }
 
-            if token.kind == TokenKind::BracketClose {
-                let location =
-                    SourceLocation::start_end(&start.location, &token.location);
+    fn set_literal(&mut self, start: Token) -> Result<Expression, ParseError> {
+        // The opening '[' that follows the '%'.
+        self.next();
 
-                return Ok(Expression::Array(Box::new(Array {
-                    values,
-                    location,
-                })));
-            }
+        let (values, end) = self.array_values()?;
+        let location = SourceLocation::start_end(&start.location, &end);
+
+        Ok(Expression::Set(Box::new(Set { values, location })))
+    }
 
-            values.push(self.expression(token)?);
+    /// Parses the values of an array or set literal, up to and including the
+    /// closing bracket.
+    ///
+    /// The return value is a tuple containing the values and the location of
+    /// the closing bracket.
+    fn array_values(
+        &mut self,
+    ) -> Result<(Vec<Expression>, SourceLocation), ParseError> {
+        // Values don't need to be separated by commas, so `[foo\n(10)]` is an
+        // array of two values, not an array containing a call.
+        self.outside_brackets(|parser| {
+            let mut values = Vec::new();
 
-            if self.peek().kind == TokenKind::Comma {
-                self.next();
+            loop {
+                let token = parser.require()?;
+
+                if token.kind == TokenKind::BracketClose {
+                    return Ok((values, token.location));
+                }
+
+                if token.kind == TokenKind::Mul {
+                    let value_token = parser.require()?;
+                    let value = parser.expression(value_token)?;
+                    let location = SourceLocation::start_end(
+                        &token.location,
+                        value.location(),
+                    );
+
+                    values.push(Expression::Splat(Box::new(Splat {
+                        value,
+                        location,
+                    })));
+                } else {
+                    values.push(parser.expression(token)?);
+                }
+
+                if parser.peek().kind == TokenKind::Comma {
+                    parser.next();
+                }
             }
-        }
+        })
     }
 
     fn field(&mut self, start: Token) -> Result<Expression, ParseError> {
@@ -1860,6 +2583,19 @@ impl Parser {
     }
 
     fn constant(&mut self, start: Token) -> Result<Expression, ParseError> {
+        let brackets = self.brackets > 0;
+        let peeked = self.peek();
+        let same_line = brackets || peeked.same_line_as(&start);
+
+        // This check comes first, such that `A { ... }` is parsed as a class
+        // literal instead of a call with a trailing block.
+        if peeked.kind == TokenKind::CurlyOpen
+            && same_line
+            && self.trailing_block_allowed == 0
+        {
+            return self.class_literal(Constant::from(start));
+        }
+
         if let Some(args) = self.arguments(&start.location)? {
             let name = Identifier::from(start);
             let location =
@@ -1873,18 +2609,7 @@ impl Parser {
             })));
         }
 
-        let peeked = self.peek();
-        let same_line = peeked.same_line_as(&start);
-        let name = Constant::from(start);
-
-        if peeked.kind == TokenKind::CurlyOpen
-            && same_line
-            && self.trailing_block_allowed == 0
-        {
-            return self.class_literal(name);
-        }
-
-        Ok(Expression::Constant(Box::new(name)))
+        Ok(Expression::Constant(Box::new(Constant::from(start))))
     }
 
     fn class_literal(
@@ -1988,12 +2713,14 @@ impl Parser {
             return Ok(None);
         }
 
+        let brackets = self.brackets > 0;
         let peeked = self.peek();
 
         // Trailing blocks are only treated as an argument if they occur on the
-        // same line as the call.
-        if peeked.location.line_range.start()
-            > start_location.line_range.start()
+        // same line as the call, unless we're inside brackets.
+        if !brackets
+            && peeked.location.line_range.start()
+                > start_location.line_range.start()
         {
             return Ok(None);
         }
@@ -2004,6 +2731,21 @@ impl Parser {
 
                 self.closure(start)?
             }
+            // `foo { ... }` is short for `foo fn { ... }`.
+            TokenKind::CurlyOpen => {
+                let start = self.next();
+                let body = self.expressions(start)?;
+                let location = body.location.clone();
+
+                Expression::Closure(Box::new(Closure {
+                    moving: false,
+                    captures: None,
+                    arguments: None,
+                    return_type: None,
+                    body,
+                    location,
+                }))
+            }
             _ => {
                 return Ok(None);
             }
@@ -2022,11 +2764,13 @@ impl Parser {
             return Ok(Some(Arguments { values: vec![block], location }));
         }
 
+        let brackets = self.brackets > 0;
         let peeked = self.peek();
 
         if peeked.kind != TokenKind::ParenOpen
-            || peeked.location.line_range.start()
-                != start_location.line_range.start()
+            || (!brackets
+                && peeked.location.line_range.start()
+                    != start_location.line_range.start())
         {
             return Ok(None);
         }
@@ -2056,6 +2800,21 @@ impl Parser {
             },
         )?;
 
+        for (index, arg) in values.iter().enumerate() {
+            let name = match arg {
+                Argument::Named(node) => &node.name,
+                _ => continue,
+            };
+            let prev = values[..index].iter().find_map(|v| match v {
+                Argument::Named(n) if n.name.name == name.name => Some(&n.name),
+                _ => None,
+            });
+
+            if let Some(prev) = prev {
+                Self::duplicate_argument(name, prev.location())?;
+            }
+        }
+
         if let Some(block) = self.trailing_block_argument(start_location)? {
             values.push(block);
         }
@@ -2233,6 +2992,21 @@ impl Parser {
         })))
     }
 
+    fn duplicate_argument(
+        name: &Identifier,
+        previous: &SourceLocation,
+    ) -> Result<(), ParseError> {
+        let (line, column) = previous.line_column();
+
+        error!(
+            name.location.clone(),
+            "The argument '{}' is already specified on line {}, column {}",
+            name.name,
+            line,
+            column
+        );
+    }
+
     fn named_argument(
         &mut self,
         start: Token,
@@ -2335,14 +3109,21 @@ impl Parser {
         } else {
             false
         };
+        let captures = self.optional_closure_captures()?;
         let arguments = self.optional_closure_arguments()?;
         let return_type = self.optional_return_type()?;
-        let body_token = self.expect(TokenKind::CurlyOpen)?;
+        let body_token = self.block_start()?;
         let body = self.expressions(body_token)?;
         let location =
             SourceLocation::start_end(&start.location, &body.location);
-        let closure =
-            Closure { moving, body, arguments, return_type, location };
+        let closure = Closure {
+            moving,
+            captures,
+            body,
+            arguments,
+            return_type,
+            location,
+        };
 
         Ok(Expression::Closure(Box::new(closure)))
     }
@@ -2440,8 +3221,23 @@ impl Parser {
     fn group_or_tuple(
         &mut self,
         start: Token,
+    ) -> Result<Expression, ParseError> {
+        self.in_brackets(|parser| parser.group_or_tuple_values(start))
+    }
+
+    fn group_or_tuple_values(
+        &mut self,
+        start: Token,
     ) -> Result<Expression, ParseError> {
         let value_token = self.require()?;
+
+        if value_token.kind == TokenKind::ParenClose {
+            error!(
+                value_token.location,
+                "Tuples must contain at least one value, such as '(10,)'"
+            );
+        }
+
         let value = self.expression(value_token)?;
 
         if self.peek().kind == TokenKind::Comma {
@@ -2535,9 +3331,11 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<Expression, ParseError> {
+        let brackets = self.brackets > 0;
         let peeked = self.peek();
-        let same_line = peeked.location.line_range.start()
-            == start.location.line_range.start();
+        let same_line = brackets
+            || peeked.location.line_range.start()
+                == start.location.line_range.start();
 
         let value = match peeked.kind {
             TokenKind::BracketOpen
@@ -2549,12 +3347,14 @@ impl Parser {
             | TokenKind::Field
             | TokenKind::Float
             | TokenKind::Fn
+            | TokenKind::Heredoc
             | TokenKind::Identifier
             | TokenKind::If
             | TokenKind::Integer
             | TokenKind::Let
             | TokenKind::Loop
             | TokenKind::Match
+            | TokenKind::Mod
             | TokenKind::Mut
             | TokenKind::Next
             | TokenKind::Nil
@@ -2610,7 +3410,7 @@ impl Parser {
                 self.next();
                 else_if.push(self.if_condition()?);
             } else {
-                let token = self.expect(TokenKind::CurlyOpen)?;
+                let token = self.block_start()?;
                 else_body = Some(self.expressions(token)?);
 
                 break;
@@ -2638,19 +3438,21 @@ impl Parser {
 
         self.expect(TokenKind::CurlyOpen)?;
 
-        let mut cases = Vec::new();
+        let (cases, close) = self.outside_brackets(|parser| {
+            let mut cases = Vec::new();
 
-        while self.peek().kind != TokenKind::CurlyClose {
-            let token = self.expect(TokenKind::Case)?;
+            while parser.peek().kind != TokenKind::CurlyClose {
+                let token = parser.expect(TokenKind::Case)?;
 
-            cases.push(self.match_case(token)?);
+                cases.push(parser.match_case(token)?);
 
-            if self.peek().kind == TokenKind::Comma {
-                self.next();
+                if parser.peek().kind == TokenKind::Comma {
+                    parser.next();
+                }
             }
-        }
 
-        let close = self.expect(TokenKind::CurlyClose)?;
+            Ok((cases, parser.expect(TokenKind::CurlyClose)?))
+        })?;
         let location =
             SourceLocation::start_end(&start.location, &close.location);
 
@@ -2676,9 +3478,14 @@ impl Parser {
         loop {
             patterns.push(self.pattern()?);
 
-            if self.peek().kind == TokenKind::Comma {
-                self.next();
-            } else {
+            if self.peek().kind != TokenKind::Comma {
+                break;
+            }
+
+            self.next();
+
+            // This allows for a trailing comma, as in `Some(a,)`.
+            if self.peek().kind == TokenKind::ParenClose {
                 break;
             }
         }
@@ -2757,6 +3564,9 @@ impl Parser {
             TokenKind::Integer => {
                 Pattern::Expression(Box::new(self.int_literal(token)))
             }
+            TokenKind::Char => {
+                Pattern::Expression(Box::new(self.char_literal(token)?))
+            }
             TokenKind::DoubleStringOpen => {
                 self.string_pattern(token, TokenKind::DoubleStringClose)?
             }
@@ -2915,7 +3725,7 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<Expression, ParseError> {
-        let body_token = self.expect(TokenKind::CurlyOpen)?;
+        let body_token = self.block_start()?;
         let body = self.expressions(body_token)?;
         let location =
             SourceLocation::start_end(&start.location, body.location());
@@ -2927,8 +3737,19 @@ impl Parser {
         &mut self,
         start: Token,
     ) -> Result<Expression, ParseError> {
+        // Without this check, `while { ... }` parses the body as the condition,
+        // and produces an error at the end of the input.
+        if self.peek().kind == TokenKind::CurlyOpen {
+            let token = self.next();
+
+            error!(
+                token.location,
+                "Expected a condition for the 'while' loop, found '{{' instead"
+            );
+        }
+
         let condition = self.expression_without_trailing_block()?;
-        let body_token = self.expect(TokenKind::CurlyOpen)?;
+        let body_token = self.block_start()?;
         let body = self.expressions(body_token)?;
         let location =
             SourceLocation::start_end(&start.location, body.location());
@@ -2938,7 +3759,7 @@ impl Parser {
 
     fn if_condition(&mut self) -> Result<IfCondition, ParseError> {
         let condition = self.expression_without_trailing_block()?;
-        let token = self.expect(TokenKind::CurlyOpen)?;
+        let token = self.block_start()?;
         let body = self.expressions(token)?;
         let location =
             SourceLocation::start_end(condition.location(), body.location());
@@ -2947,14 +3768,78 @@ impl Parser {
     }
 
     fn next(&mut self) -> Token {
+        if let Some(token) = self.peeked.take() {
+            return token;
+        }
+
         loop {
-            let token =
-                self.peeked.take().unwrap_or_else(|| self.lexer.next_token());
+            let token = self.lexer.next_token();
 
             match token.kind {
-                TokenKind::Comment | TokenKind::Whitespace => {}
-                _ => return token,
+                TokenKind::Comment => {
+                    self.doc_comment(&token);
+
+                    if let Some(comments) = self.comments.as_mut() {
+                        comments.push(Comment::from(token));
+                    }
+                }
+                TokenKind::Whitespace => {}
+                _ => {
+                    self.record_documentation(&token);
+                    return token;
+                }
+            }
+        }
+    }
+
+    /// Adds a comment to the lines that may document the next definition.
+    ///
+    /// Comments at the end of a line of code are ignored, and comments
+    /// separated from the previous comment by an empty line start a new set
+    /// of lines.
+    fn doc_comment(&mut self, token: &Token) {
+        let line = *token.location.line_range.start();
+
+        if line == self.last_line {
+            self.doc_comments.clear();
+            return;
+        }
+
+        if line != self.last_line + 1 {
+            self.doc_comments.clear();
+        }
+
+        self.doc_comments.push(token.value.clone());
+        self.last_line = line;
+    }
+
+    fn record_documentation(&mut self, token: &Token) {
+        let line = *token.location.line_range.start();
+        let column = *token.location.column_range.start();
+
+        self.documentation =
+            if !self.doc_comments.is_empty() && line == self.last_line + 1 {
+                Some((line, column, self.doc_comments.join("\n")))
+            } else {
+                None
+            };
+
+        self.doc_comments.clear();
+        self.last_line = *token.location.line_range.end();
+    }
+
+    /// Returns the documentation of the definition starting with `start`.
+    fn documentation(&mut self, start: &Token) -> Option<String> {
+        let line = *start.location.line_range.start();
+        let column = *start.location.column_range.start();
+
+        match self.documentation.take() {
+            Some((doc_line, doc_column, doc))
+                if doc_line == line && doc_column == column =>
+            {
+                Some(doc)
             }
+            _ => None,
         }
     }
 
@@ -3010,6 +3895,113 @@ impl Parser {
         Ok(token)
     }
 
+    /// Records a syntax error and skips to the start of the next expression,
+    /// or returns the error if the parser isn't to recover from errors.
+    ///
+    /// The next expression is the first token that starts on a new line and
+    /// isn't nested in a block. Closing curly braces and the end of `do`
+    /// blocks are left in place, such that the surrounding block is still
+    /// closed properly.
+    ///
+    /// The `do_block` argument is `true` if the error is produced inside a
+    /// block opened using `do`. Since `do` and `end` aren't keywords, they're
+    /// only treated as the start and end of a block when the enclosing block
+    /// is opened using `do`. This way a variable called `end` in a block
+    /// opened using `{` doesn't change where we stop skipping tokens.
+    fn recover(
+        &mut self,
+        error: ParseError,
+        do_block: bool,
+    ) -> Result<(), ParseError> {
+        let Some(errors) = self.errors.as_mut() else { return Err(error) };
+        let mut line = *error.location.line_range.end();
+        let mut blocks = vec![do_block];
+
+        errors.push(error);
+
+        loop {
+            let token = self.peek();
+            let start_line = *token.location.line_range.start();
+            let end_line = *token.location.line_range.end();
+            let nested = blocks.len() > 1;
+            let in_do = *blocks.last().unwrap();
+            let is_do = in_do && Self::is_identifier(token, DO_BLOCK_START);
+            let is_end = in_do && Self::is_identifier(token, DO_BLOCK_END);
+
+            match token.kind {
+                TokenKind::Null => break,
+                _ if !nested && start_line > line => break,
+                TokenKind::CurlyClose if !nested => break,
+                _ if is_end && !nested => break,
+                TokenKind::CurlyOpen => blocks.push(false),
+                TokenKind::CurlyClose => {
+                    blocks.pop();
+                }
+                _ if is_do => blocks.push(true),
+                _ if is_end => {
+                    blocks.pop();
+                }
+                _ => {}
+            }
+
+            line = end_line;
+            self.next();
+        }
+
+        Ok(())
+    }
+
+    fn block_start(&mut self) -> Result<Token, ParseError> {
+        let token = self.require()?;
+
+        if token.kind == TokenKind::CurlyOpen
+            || Self::is_identifier(&token, DO_BLOCK_START)
+        {
+            return Ok(token);
+        }
+
+        self.require_valid_token(&token)?;
+        error!(
+            token.location,
+            "Expected a '{{' or 'do', found '{}' instead", token.value
+        );
+    }
+
+    fn next_is_block_start(&mut self) -> bool {
+        let token = self.peek();
+
+        token.kind == TokenKind::CurlyOpen
+            || Self::is_identifier(token, DO_BLOCK_START)
+    }
+
+    /// Returns `true` if `token` closes the block opened by `start`.
+    ///
+    /// A block opened using `{` must be closed using `}`, and a block opened
+    /// using `do` must be closed using `end`.
+    fn block_end(
+        &self,
+        start: &Token,
+        token: &Token,
+    ) -> Result<bool, ParseError> {
+        if start.kind != TokenKind::Identifier {
+            return Ok(token.kind == TokenKind::CurlyClose);
+        }
+
+        if token.kind == TokenKind::CurlyClose {
+            error!(
+                token.location.clone(),
+                "Blocks starting with 'do' must be closed using 'end', \
+                found '}' instead"
+            );
+        }
+
+        Ok(Self::is_identifier(token, DO_BLOCK_END))
+    }
+
+    fn is_identifier(token: &Token, name: &str) -> bool {
+        token.kind == TokenKind::Identifier && token.value == name
+    }
+
     fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
         let token = self.require()?;
 
@@ -3026,30 +4018,33 @@ impl Parser {
     where
         F: FnMut(&mut Self, Token) -> Result<T, ParseError>,
     {
-        let mut values = Vec::new();
         let open_token = self.expect(open)?;
 
-        loop {
-            let token = self.require()?;
+        self.in_brackets(|parser| {
+            let mut values = Vec::new();
 
-            if token.kind == close {
-                return Ok((
-                    values,
-                    SourceLocation::start_end(
-                        &open_token.location,
-                        &token.location,
-                    ),
-                ));
-            }
+            loop {
+                let token = parser.require()?;
 
-            values.push(func(self, token)?);
+                if token.kind == close {
+                    return Ok((
+                        values,
+                        SourceLocation::start_end(
+                            &open_token.location,
+                            &token.location,
+                        ),
+                    ));
+                }
 
-            if !values.is_empty() && self.peek().kind != close {
-                self.expect(TokenKind::Comma)?;
-            } else if self.peek().kind == TokenKind::Comma {
-                self.next();
+                values.push(func(parser, token)?);
+
+                if !values.is_empty() && parser.peek().kind != close {
+                    parser.expect(TokenKind::Comma)?;
+                } else if parser.peek().kind == TokenKind::Comma {
+                    parser.next();
+                }
             }
-        }
+        })
     }
 
     fn without_trailing_block<R, F>(&mut self, func: F) -> R
@@ -3065,6 +4060,60 @@ impl Parser {
         retval
     }
 
+    fn in_brackets<R, F>(&mut self, func: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        self.brackets += 1;
+
+        let retval = func(self);
+
+        self.brackets -= 1;
+
+        retval
+    }
+
+    fn outside_brackets<R, F>(&mut self, func: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        let brackets = self.brackets;
+
+        self.brackets = 0;
+
+        let retval = func(self);
+
+        self.brackets = brackets;
+
+        retval
+    }
+
+    /// Returns an error if `start` is a `pub` keyword that comes before the
+    /// keyword of a definition (e.g. `pub fn foo`), instead of after it (e.g.
+    /// `fn pub foo`).
+    fn misplaced_pub(&mut self, start: &Token) -> Option<ParseError> {
+        if start.kind != TokenKind::Pub {
+            return None;
+        }
+
+        let next = self.peek();
+
+        match next.kind {
+            TokenKind::Fn
+            | TokenKind::Class
+            | TokenKind::Trait
+            | TokenKind::Let => Some(ParseError {
+                message: format!(
+                    "The 'pub' keyword must come after the '{}' keyword, \
+                    such as '{} pub ...'",
+                    next.value, next.value
+                ),
+                location: start.location.clone(),
+            }),
+            _ => None,
+        }
+    }
+
     fn next_is_public(&mut self) -> bool {
         if self.peek().kind == TokenKind::Pub {
             self.next();
@@ -3080,6 +4129,7 @@ mod tests {
     use super::*;
     use similar_asserts::assert_eq;
     use std::ops::RangeInclusive;
+    use std::sync::Arc;
 
     pub(crate) fn cols(start: usize, stop: usize) -> SourceLocation {
         SourceLocation::new(1..=1, start..=stop)
@@ -3195,6 +4245,265 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reparse() {
+        let old = "import a\nfn foo {}\nfn bar {}\n";
+        let new = "import a\nfn foo {\n  10\n}\nfn bar {}\n";
+        let previous = parser(old).parse().unwrap();
+        let module =
+            Parser::reparse(new.into(), "test.inko".into(), previous, 2)
+                .unwrap();
+
+        assert_eq!(module, parser(new).parse().unwrap());
+    }
+
+    #[test]
+    fn test_reparse_with_expressions_on_the_same_line() {
+        let old = "fn foo {} fn bar {\n}\n";
+        let new = "fn foo {} fn bar {\n  10\n}\n";
+        let previous = parser(old).parse().unwrap();
+        let module =
+            Parser::reparse(new.into(), "test.inko".into(), previous, 2)
+                .unwrap();
+
+        assert_eq!(module, parser(new).parse().unwrap());
+    }
+
+    #[test]
+    fn test_reparse_with_documentation() {
+        let old = "fn foo {}\n\n# Foo\n#\n# Bar\nfn bar {}\n";
+        let new = "fn foo {}\n\n# Foo\n#\n# Bar\nfn bar {\n  10\n}\n";
+        let previous = parser(old).parse().unwrap();
+        let module =
+            Parser::reparse(new.into(), "test.inko".into(), previous, 6)
+                .unwrap();
+        let docs = match &module.expressions[1] {
+            TopLevelExpression::DefineMethod(n) => n.documentation.clone(),
+            _ => panic!("expected a method"),
+        };
+
+        assert_eq!(module, parser(new).parse().unwrap());
+        assert_eq!(docs, Some("Foo\n\nBar".to_string()));
+    }
+
+    #[test]
+    fn test_reparse_with_an_error() {
+        let old = "fn foo {}\nfn bar {}";
+        let new = "fn foo {}\nfn bar {";
+        let previous = parser(old).parse().unwrap();
+
+        assert!(Parser::reparse(new.into(), "test.inko".into(), previous, 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_with_comments() {
+        let (module, comments) =
+            parser("# foo\nfn foo {} # bar").parse_with_comments().unwrap();
+
+        assert_eq!(module.expressions.len(), 1);
+        assert_eq!(
+            comments,
+            vec![
+                Comment { value: "foo".to_string(), location: cols(1, 5) },
+                Comment {
+                    value: "bar".to_string(),
+                    location: location(2..=2, 11..=15)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_documentation() {
+        let module = parse(
+            "# Foo\n#\n# Bar\nfn foo {}\n\n# Baz\n\nfn bar {}\n\n\
+            # A\nclass A {\n  # B\n  fn b {} # C\n  fn c {}\n}\n\
+            # D\ntrait D {\n  # E\n  fn e\n}",
+        );
+        let docs = |expr: &TopLevelExpression| match expr {
+            TopLevelExpression::DefineMethod(n) => n.documentation.clone(),
+            TopLevelExpression::DefineClass(n) => n.documentation.clone(),
+            TopLevelExpression::DefineTrait(n) => n.documentation.clone(),
+            _ => panic!("expected a definition"),
+        };
+
+        assert_eq!(
+            docs(&module.expressions[0]),
+            Some("Foo\n\nBar".to_string())
+        );
+        assert_eq!(docs(&module.expressions[1]), None);
+        assert_eq!(docs(&module.expressions[2]), Some("A".to_string()));
+        assert_eq!(docs(&module.expressions[3]), Some("D".to_string()));
+
+        let TopLevelExpression::DefineClass(class) = &module.expressions[2]
+        else {
+            panic!("expected a class");
+        };
+        let methods = class
+            .body
+            .values
+            .iter()
+            .map(|expr| match expr {
+                ClassExpression::DefineMethod(n) => n.documentation.clone(),
+                _ => panic!("expected a method"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(methods, vec![Some("B".to_string()), None]);
+
+        let TopLevelExpression::DefineTrait(tr) = &module.expressions[3] else {
+            panic!("expected a trait");
+        };
+
+        assert_eq!(tr.body.values[0].documentation, Some("E".to_string()));
+    }
+
+    #[test]
+    fn test_documentation_with_comments() {
+        let (module, comments) =
+            parser("# Foo\nfn foo {}\n# Bar").parse_with_comments().unwrap();
+        let TopLevelExpression::DefineMethod(method) = &module.expressions[0]
+        else {
+            panic!("expected a method");
+        };
+
+        // Comments used as documentation are still included in the list of
+        // comments.
+        assert_eq!(method.documentation, Some("Foo".to_string()));
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].value, "Bar");
+    }
+
+    #[test]
+    fn test_parse_with_recovery() {
+        let module =
+            parser("fn a {\n  10\n}\nfn b {}").parse_with_recovery().unwrap();
+
+        assert_eq!(module.expressions.len(), 2);
+
+        let errors = parser("fn a {\n  let = 10\n  foo(,)\n}\nfn b {}")
+            .parse_with_recovery()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.location)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![location(2..=2, 7..=7), location(3..=3, 7..=7)]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_recovery_in_nested_blocks() {
+        let errors = parser(
+            "fn a {\n  if true {\n    let = 10\n  }\n  10 *\n  20\n}\n\
+            class 10 {}",
+        )
+        .parse_with_recovery()
+        .unwrap_err()
+        .into_iter()
+        .map(|e| e.location)
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![location(3..=3, 9..=9), location(8..=8, 7..=8)]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_recovery_and_end_identifiers() {
+        let errors = |input: &str| {
+            parser(input)
+                .parse_with_recovery()
+                .unwrap_err()
+                .into_iter()
+                .map(|e| e.location)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            errors("fn a {\n  foo(,) { let x = end }\n  10\n}\nfn b {}"),
+            vec![location(2..=2, 7..=7)]
+        );
+        assert_eq!(
+            errors("fn a do\n  foo(,) do 10 end\n  10\nend\nfn b {}"),
+            vec![location(2..=2, 7..=7)]
+        );
+    }
+
+    #[test]
+    fn test_node_span() {
+        assert_eq!(expr("[\n  10,\n  20\n]").span(), (1, 1, 4, 1));
+        assert_eq!(expr("[10,\n  200]").span(), (1, 1, 2, 6));
+        assert_eq!(top(parse("fn foo {\n  10\n}")).span(), (1, 1, 3, 1));
+        assert_eq!(
+            top(parse("class A {\n  fn foo {\n    10\n  }\n}")).span(),
+            (1, 1, 5, 1)
+        );
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        let same = |with: &str, without: &str| {
+            parse(with).structural_eq(&parse(without))
+        };
+
+        // Arrays and sets.
+        assert!(same("fn a { [1, 2,] }", "fn a { [1, 2] }"));
+        assert!(same("fn a { %[1, 2,] }", "fn a { %[1, 2] }"));
+
+        // Tuples and class literals.
+        assert!(same("fn a { (1, 2,) }", "fn a { (1, 2) }"));
+        assert!(same(
+            "fn a { A { @a = 1, @b = 2, } }",
+            "fn a { A { @a = 1, @b = 2 } }"
+        ));
+
+        // Arguments of calls.
+        assert!(same("fn a { foo(1, 2,) }", "fn a { foo(1, 2) }"));
+        assert!(same("fn a { foo(a: 1, b: 2,) }", "fn a { foo(a: 1, b: 2) }"));
+        assert!(same(
+            "fn a { foo.bar(1,) fn {} }",
+            "fn a { foo.bar(1) fn {} }"
+        ));
+
+        // Arguments of methods and closures.
+        assert!(same("fn a(a: A, b: B,) {}", "fn a(a: A, b: B) {}"));
+        assert!(same("fn a { fn (a, b,) {} }", "fn a { fn (a, b) {} }"));
+        assert!(same("fn a { fn [a, b,] {} }", "fn a { fn [a, b] {} }"));
+
+        // Type parameters and type arguments.
+        assert!(same("fn a[A, B,] {}", "fn a[A, B] {}"));
+        assert!(same("class A[B, C,] {}", "class A[B, C] {}"));
+        assert!(same("fn a -> Array[A, B,] {}", "fn a -> Array[A, B] {}"));
+        assert!(same("fn a -> fn (A, B,) {}", "fn a -> fn (A, B) {}"));
+        assert!(same("fn a -> (A, B,) {}", "fn a -> (A, B) {}"));
+
+        // Import symbols.
+        assert!(same("import a.(b, c,)", "import a.(b, c)"));
+
+        // Enum variants and patterns.
+        assert!(same(
+            "class enum A { case B(C, D,) }",
+            "class enum A { case B(C, D) }"
+        ));
+        assert!(same(
+            "fn a { match a { case B(c, d,) -> 1 } }",
+            "fn a { match a { case B(c, d) -> 1 } }"
+        ));
+        assert!(same(
+            "fn a { match a { case (c, d,) -> 1 } }",
+            "fn a { match a { case (c, d) -> 1 } }"
+        ));
+        assert!(same(
+            "fn a { match a { case { @a = b, } -> 1 } }",
+            "fn a { match a { case { @a = b } -> 1 } }"
+        ));
+    }
+
     #[test]
     fn test_imports() {
         assert_eq!(
@@ -3474,6 +4783,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_relative_imports() {
+        let node = top(parse("import self.foo.bar"));
+
+        assert_eq!(
+            node,
+            TopLevelExpression::Import(Box::new(Import {
+                path: ImportPath {
+                    steps: vec![
+                        Identifier {
+                            name: "self".to_string(),
+                            location: cols(8, 11)
+                        },
+                        Identifier {
+                            name: "foo".to_string(),
+                            location: cols(13, 15)
+                        },
+                        Identifier {
+                            name: "bar".to_string(),
+                            location: cols(17, 19)
+                        }
+                    ],
+                    location: cols(8, 19)
+                },
+                symbols: None,
+                tags: None,
+                include: true,
+                location: cols(1, 19)
+            }))
+        );
+
+        match node {
+            TopLevelExpression::Import(node) => {
+                assert!(node.path.is_relative())
+            }
+            _ => panic!("Expected an import"),
+        }
+
+        match top(parse("import foo.bar")) {
+            TopLevelExpression::Import(node) => {
+                assert!(!node.path.is_relative())
+            }
+            _ => panic!("Expected an import"),
+        }
+    }
+
     #[test]
     fn test_imports_with_aliases() {
         assert_eq!(
@@ -3585,6 +4940,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_duplicate_arguments() {
+        assert_error!("fn a(a: A, a: B) {}", cols(12, 12));
+        assert_error!("fn a(a: A, b: B, a: C) {}", cols(18, 18));
+        assert_error_expr!("a(b: 1, b: 2)", cols(9, 9));
+        assert_error_expr!("a(1, b: 1, c: 2, b: 3)", cols(18, 18));
+
+        let mut parser = parser("a(b: 1, b: 2)");
+        let start = parser.require().unwrap();
+        let err = parser
+            .expression(start)
+            .map(|_| ())
+            .expect_err("Expected a syntax error");
+
+        assert_eq!(
+            err.message,
+            "The argument 'b' is already specified on line 1, column 3"
+        );
+
+        assert_eq!(parse("fn a(a: A, b: B) {}").expressions.len(), 1);
+        assert!(matches!(expr("a(b: 1, c: 2)"), Expression::Call(_)));
+    }
+
     #[test]
     fn test_invalid_imports() {
         assert_error!("import foo.(bar as Baz)", cols(20, 22));
@@ -3595,6 +4973,9 @@ mod tests {
         assert_error!("import foo.", cols(11, 11));
         assert_error!("import foo.(", cols(12, 12));
         assert_error!("import foo.)", cols(12, 12));
+        assert_error!("import self", cols(8, 11));
+        assert_error!("import self.(Foo)", cols(8, 11));
+        assert_error!("import foo.self", cols(12, 15));
     }
 
     #[test]
@@ -3712,6 +5093,24 @@ mod tests {
         assert_error!("let A = (B.new)", cols(11, 11));
     }
 
+    #[test]
+    fn test_misplaced_pub() {
+        assert_error!("pub fn foo {}", cols(1, 3));
+        assert_error!("pub class A {}", cols(1, 3));
+        assert_error!("pub trait A {}", cols(1, 3));
+        assert_error!("pub let A = 10", cols(1, 3));
+        assert_error!("class A { pub fn foo {} }", cols(11, 13));
+        assert_error!("class A { pub let @a: A }", cols(11, 13));
+
+        let error = parser("pub fn foo {}").parse().unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "The 'pub' keyword must come after the 'fn' keyword, \
+            such as 'fn pub ...'"
+        );
+    }
+
     #[test]
     fn test_constant_with_binary_operation() {
         assert_eq!(
@@ -4068,6 +5467,7 @@ mod tests {
         assert_eq!(
             top(parse("fn foo {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4089,6 +5489,7 @@ mod tests {
         assert_eq!(
             top(parse("fn FOO {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4110,6 +5511,7 @@ mod tests {
         assert_eq!(
             top(parse("fn pub foo {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: true,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4131,6 +5533,7 @@ mod tests {
         assert_eq!(
             top(parse("fn 123 {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4152,6 +5555,7 @@ mod tests {
         assert_eq!(
             top(parse("fn ab= {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4173,6 +5577,7 @@ mod tests {
         assert_eq!(
             top(parse("fn 12= {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4194,6 +5599,7 @@ mod tests {
         assert_eq!(
             top(parse("fn let {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4218,6 +5624,7 @@ mod tests {
         assert_eq!(
             top(parse("fn foo [T] {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4250,6 +5657,7 @@ mod tests {
         assert_eq!(
             top(parse("fn foo [T: A + B] {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4307,6 +5715,7 @@ mod tests {
         assert_eq!(
             top(parse("fn foo (a: A, b: B) {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4368,6 +5777,7 @@ mod tests {
         assert_eq!(
             top(parse("fn foo -> A {}")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4390,16 +5800,45 @@ mod tests {
                     values: Vec::new(),
                     location: cols(13, 14)
                 }),
-                location: cols(1, 14)
+                location: cols(1, 14)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_method_with_body() {
+        assert_eq!(
+            top(parse("fn foo { 10 }")),
+            TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
+                public: false,
+                operator: false,
+                kind: MethodKind::Instance,
+                name: Identifier {
+                    name: "foo".to_string(),
+                    location: cols(4, 6)
+                },
+                type_parameters: None,
+                arguments: None,
+                return_type: None,
+                body: Some(Expressions {
+                    values: vec![Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(10, 11)
+                    }))],
+                    location: cols(8, 13)
+                }),
+                location: cols(1, 13),
             }))
         );
     }
 
     #[test]
-    fn test_method_with_body() {
+    fn test_method_with_expression_body() {
         assert_eq!(
-            top(parse("fn foo { 10 }")),
+            top(parse("fn foo -> A = 10")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Instance,
@@ -4409,17 +5848,34 @@ mod tests {
                 },
                 type_parameters: None,
                 arguments: None,
-                return_type: None,
+                return_type: Some(Type::Named(Box::new(TypeName {
+                    name: Constant {
+                        source: None,
+                        name: "A".to_string(),
+                        location: cols(11, 11),
+                    },
+                    arguments: None,
+                    location: cols(11, 11)
+                }))),
                 body: Some(Expressions {
                     values: vec![Expression::Int(Box::new(IntLiteral {
                         value: "10".to_string(),
-                        location: cols(10, 11)
+                        location: cols(15, 16)
                     }))],
-                    location: cols(8, 13)
+                    location: cols(15, 16)
                 }),
-                location: cols(1, 13),
+                location: cols(1, 16),
             }))
         );
+
+        assert!(parse("fn foo = 10\nfn bar = 20")
+            .structural_eq(&parse("fn foo { 10 }\nfn bar { 20 }")));
+        assert!(parse("class A { fn foo(a: Int) -> Int = a * 2 }")
+            .structural_eq(&parse(
+                "class A { fn foo(a: Int) -> Int { a * 2 } }"
+            )));
+        assert!(parse("trait A { fn foo = 10 }")
+            .structural_eq(&parse("trait A { fn foo { 10 } }")));
     }
 
     #[test]
@@ -4427,6 +5883,7 @@ mod tests {
         assert_eq!(
             top(parse("fn extern foo")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Extern,
@@ -4445,6 +5902,7 @@ mod tests {
         assert_eq!(
             top(parse("fn extern foo(...)")),
             TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
                 public: false,
                 operator: false,
                 kind: MethodKind::Extern,
@@ -4471,10 +5929,28 @@ mod tests {
         assert_error!("fn foo [A: ] {}", cols(12, 12));
         assert_error!("fn foo (A: ) {}", cols(9, 9));
         assert_error!("fn foo (a: ) {}", cols(12, 12));
+        assert_error!("fn foo (a: A, b: A = a) {}", cols(20, 20));
         assert_error!("fn foo -> {}", cols(11, 11));
         assert_error!("fn foo {", cols(8, 8));
         assert_error!("fn foo", cols(6, 6));
+        assert_error!("fn foo =", cols(8, 8));
+        assert_error!("fn foo = }", cols(8, 8));
         assert_error!("fn extern foo[T](arg: T)", cols(14, 14));
+        assert_error!("fn extern foo(..., ...)", cols(18, 18));
+        assert_error!("fn extern foo(..., a: Int)", cols(18, 18));
+        assert_error!("fn foo(...)", cols(8, 8));
+    }
+
+    #[test]
+    fn test_variadic_argument_error_message() {
+        let error =
+            parser("fn extern foo(a: Int, ..., b: Int)").parse().unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "The '...' argument must be the last argument, found ',' after it"
+        );
+        assert_eq!(error.location, cols(26, 26));
     }
 
     #[test]
@@ -4482,6 +5958,7 @@ mod tests {
         assert_eq!(
             top(parse("class A {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4501,6 +5978,7 @@ mod tests {
         assert_eq!(
             top(parse("class pub A {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: true,
                 name: Constant {
                     source: None,
@@ -4523,6 +6001,7 @@ mod tests {
         assert_eq!(
             top(parse("class extern A {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4591,6 +6070,7 @@ mod tests {
         assert_eq!(
             top(parse("class async A {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4613,6 +6093,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn async foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4624,6 +6105,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::Async,
@@ -4650,6 +6132,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn async mut foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4661,6 +6144,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::AsyncMutable,
@@ -4690,6 +6174,7 @@ mod tests {
         assert_eq!(
             top(parse("class A[B: X, C] {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4742,6 +6227,7 @@ mod tests {
         assert_eq!(
             top(parse("class A[B: a.X] {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4784,11 +6270,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_class_with_type_parameter_with_multiple_requirements() {
+        assert_eq!(
+            top(parse("class A[B: X + mut] {}")),
+            TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
+                public: false,
+                name: Constant {
+                    source: None,
+                    name: "A".to_string(),
+                    location: cols(7, 7)
+                },
+                kind: ClassKind::Regular,
+                type_parameters: Some(TypeParameters {
+                    values: vec![TypeParameter {
+                        name: Constant {
+                            source: None,
+                            name: "B".to_string(),
+                            location: cols(9, 9)
+                        },
+                        requirements: Some(Requirements {
+                            values: vec![
+                                Requirement::Trait(TypeName {
+                                    name: Constant {
+                                        source: None,
+                                        name: "X".to_string(),
+                                        location: cols(12, 12),
+                                    },
+                                    arguments: None,
+                                    location: cols(12, 12)
+                                }),
+                                Requirement::Mutable(cols(16, 18))
+                            ],
+                            location: cols(12, 18)
+                        }),
+                        location: cols(9, 18)
+                    }],
+                    location: cols(8, 19)
+                }),
+                body: ClassExpressions {
+                    values: Vec::new(),
+                    location: cols(21, 22)
+                },
+                location: cols(1, 22)
+            }))
+        );
+    }
+
     #[test]
     fn test_class_with_instance_method() {
         assert_eq!(
             top(parse("class A { fn foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4800,6 +6335,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::Instance,
@@ -4826,6 +6362,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn pub foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4837,6 +6374,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: true,
                             operator: false,
                             kind: MethodKind::Instance,
@@ -4866,6 +6404,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn move foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4877,6 +6416,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::Moving,
@@ -4906,6 +6446,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn mut foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4917,6 +6458,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::Mutable,
@@ -4946,6 +6488,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { fn static foo {} }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -4957,6 +6500,7 @@ mod tests {
                 body: ClassExpressions {
                     values: vec![ClassExpression::DefineMethod(Box::new(
                         DefineMethod {
+                            documentation: None,
                             public: false,
                             operator: false,
                             kind: MethodKind::Static,
@@ -4981,11 +6525,87 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_class_with_sections() {
+        assert_eq!(
+            top(parse("class A { static { fn foo {} } }")),
+            TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
+                public: false,
+                name: Constant {
+                    source: None,
+                    name: "A".to_string(),
+                    location: cols(7, 7)
+                },
+                kind: ClassKind::Regular,
+                type_parameters: None,
+                body: ClassExpressions {
+                    values: vec![ClassExpression::Section(Box::new(
+                        ClassSection {
+                            kind: SectionKind::Static,
+                            body: ClassExpressions {
+                                values: vec![ClassExpression::DefineMethod(
+                                    Box::new(DefineMethod {
+                                        documentation: None,
+                                        public: false,
+                                        operator: false,
+                                        kind: MethodKind::Static,
+                                        name: Identifier {
+                                            name: "foo".to_string(),
+                                            location: cols(23, 25)
+                                        },
+                                        type_parameters: None,
+                                        arguments: None,
+                                        return_type: None,
+                                        body: Some(Expressions {
+                                            values: Vec::new(),
+                                            location: cols(27, 28)
+                                        }),
+                                        location: cols(20, 28)
+                                    })
+                                )],
+                                location: cols(18, 30)
+                            },
+                            location: cols(11, 30)
+                        }
+                    ))],
+                    location: cols(9, 32)
+                },
+                location: cols(1, 32)
+            }))
+        );
+
+        let pairs = [
+            (
+                "class A { pub { let @a: A\nfn b {} } }",
+                "class A { pub { let pub @a: A\nfn pub b {} } }",
+            ),
+            (
+                "class A { pub do\nfn static b {}\nend }",
+                "class A { pub { fn pub static b {} } }",
+            ),
+            (
+                "class A { pub { static { fn b {} } } }",
+                "class A { pub { static { fn pub static b {} } } }",
+            ),
+        ];
+
+        for (input, expected) in pairs {
+            assert!(
+                parse(input).structural_eq(&parse(expected)),
+                "{:?} should be the same as {:?}",
+                input,
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_class_with_field() {
         assert_eq!(
             top(parse("class A { let @foo: A }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5023,6 +6643,7 @@ mod tests {
         assert_eq!(
             top(parse("class A { let pub @foo: A }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5065,6 +6686,11 @@ mod tests {
         assert_error!("class A {", cols(9, 9));
         assert_error!("class extern A[T] {", cols(15, 15));
         assert_error!("class extern A { fn foo {  } }", cols(18, 19));
+        assert_error!("class A { static { fn mut foo {} } }", cols(27, 29));
+        assert_error!("class A { static { let @a: A } }", cols(20, 28));
+        assert_error!("class A { pub { case A } }", cols(17, 20));
+        assert_error!("class A { pub 10 }", cols(11, 13));
+        assert_error!("class extern A { pub { let @a: A } }", cols(18, 20));
     }
 
     #[test]
@@ -5235,6 +6861,7 @@ mod tests {
                 },
                 body: ImplementationExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5288,6 +6915,7 @@ mod tests {
                 },
                 body: ImplementationExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5321,6 +6949,7 @@ mod tests {
                 },
                 body: ImplementationExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Async,
@@ -5388,6 +7017,7 @@ mod tests {
                 },
                 body: ImplementationExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Static,
@@ -5424,6 +7054,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A {}")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5443,6 +7074,7 @@ mod tests {
         assert_eq!(
             top(parse("trait pub A {}")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: true,
                 name: Constant {
                     source: None,
@@ -5465,6 +7097,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A: B + C {}")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5506,6 +7139,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A: a.B {}")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5542,6 +7176,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A[B: X, C] {}")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5597,6 +7232,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5607,6 +7243,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5632,6 +7269,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5642,6 +7280,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5667,6 +7306,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo -> A }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5677,6 +7317,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5710,6 +7351,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo (a: A) }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5720,6 +7362,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5764,6 +7407,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo [A] }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5774,6 +7418,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5810,6 +7455,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn foo {} }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5820,6 +7466,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Instance,
@@ -5848,6 +7495,7 @@ mod tests {
         assert_eq!(
             top(parse("trait A { fn move foo {} }")),
             TopLevelExpression::DefineTrait(Box::new(DefineTrait {
+                documentation: None,
                 public: false,
                 name: Constant {
                     source: None,
@@ -5858,6 +7506,7 @@ mod tests {
                 requirements: None,
                 body: TraitExpressions {
                     values: vec![DefineMethod {
+                        documentation: None,
                         public: false,
                         operator: false,
                         kind: MethodKind::Moving,
@@ -5894,6 +7543,7 @@ mod tests {
         assert_eq!(
             top(parse("class builtin A {}")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 kind: ClassKind::Builtin,
                 name: Constant {
@@ -5936,6 +7586,38 @@ mod tests {
                 location: cols(1, 3)
             }))
         );
+
+        assert_eq!(
+            expr("0x1F"),
+            Expression::Int(Box::new(IntLiteral {
+                value: "0x1F".to_string(),
+                location: cols(1, 4)
+            }))
+        );
+
+        assert_eq!(
+            expr("-0xff"),
+            Expression::Int(Box::new(IntLiteral {
+                value: "-0xff".to_string(),
+                location: cols(1, 5)
+            }))
+        );
+
+        assert_eq!(
+            expr("0o755"),
+            Expression::Int(Box::new(IntLiteral {
+                value: "0o755".to_string(),
+                location: cols(1, 5)
+            }))
+        );
+
+        assert_eq!(
+            expr("-0b1010"),
+            Expression::Int(Box::new(IntLiteral {
+                value: "-0b1010".to_string(),
+                location: cols(1, 7)
+            }))
+        );
     }
 
     #[test]
@@ -5963,6 +7645,49 @@ mod tests {
                 location: cols(1, 5)
             }))
         );
+
+        assert_eq!(
+            expr("1.0e3"),
+            Expression::Float(Box::new(FloatLiteral {
+                value: "1.0e3".to_string(),
+                location: cols(1, 5)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_char_expression() {
+        assert_eq!(
+            expr("?a"),
+            Expression::Char(Box::new(CharLiteral {
+                value: 'a',
+                location: cols(1, 2)
+            }))
+        );
+
+        assert_eq!(
+            expr("?\\n"),
+            Expression::Char(Box::new(CharLiteral {
+                value: '\n',
+                location: cols(1, 3)
+            }))
+        );
+
+        assert_eq!(
+            expr("?\\u{1F600}"),
+            Expression::Char(Box::new(CharLiteral {
+                value: '\u{1F600}',
+                location: cols(1, 10)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_invalid_char_expression() {
+        assert_error_expr!("?", cols(1, 1));
+        assert_error_expr!("? a", cols(1, 1));
+        assert_error_expr!("?ab", cols(1, 3));
+        assert_error!("fn a { ?\\q }", cols(8, 10));
     }
 
     #[test]
@@ -6000,7 +7725,74 @@ mod tests {
 
     #[test]
     fn test_invalid_single_string() {
-        assert_error_expr!("'foo", cols(4, 4));
+        assert_error_expr!("'foo", cols(1, 1));
+        assert_error_expr!("'", cols(1, 1));
+        assert_error_expr!("10 + 'foo", cols(6, 6));
+    }
+
+    #[test]
+    fn test_unterminated_string_message() {
+        let mut parser = parser("fn foo {\n  \"foo");
+        let error = parser.parse().unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "Unterminated string literal starting at line 2, column 3"
+        );
+        assert_eq!(error.location, location(2..=2, 3..=3));
+    }
+
+    #[test]
+    fn test_heredoc_expression() {
+        assert_eq!(
+            expr("<<~END\n  foo\n    bar\n\n  baz\n  END"),
+            Expression::SingleString(Box::new(StringLiteral {
+                value: Some(StringText {
+                    value: "foo\n  bar\n\nbaz\n".to_string(),
+                    location: location(1..=6, 1..=5)
+                }),
+                location: location(1..=6, 1..=5)
+            }))
+        );
+
+        assert_eq!(
+            expr("<<~END\nEND"),
+            Expression::SingleString(Box::new(StringLiteral {
+                value: None,
+                location: location(1..=2, 1..=3)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_heredoc_followed_by_expression() {
+        let Expression::Call(call) = expr("foo(<<~END\n  a\n  END, 10)") else {
+            panic!("expected a call");
+        };
+        let args = call.arguments.unwrap().values;
+
+        assert_eq!(args.len(), 2);
+        assert_eq!(
+            args[0],
+            Argument::Positional(Expression::SingleString(Box::new(
+                StringLiteral {
+                    value: Some(StringText {
+                        value: "a\n".to_string(),
+                        location: location(1..=3, 5..=5)
+                    }),
+                    location: location(1..=3, 5..=5)
+                }
+            )))
+        );
+        assert!(matches!(args[1], Argument::Positional(Expression::Int(_))));
+        assert!(matches!(expr("<<~END\n  a\n  END.size"), Expression::Call(_)));
+    }
+
+    #[test]
+    fn test_invalid_heredoc() {
+        assert_error_expr!("<<~END\nfoo", cols(1, 6));
+        assert_error_expr!("<<~END\nfoo\nENDING", cols(1, 6));
+        assert_error!("fn a { <<~END foo\nEND }", cols(8, 15));
     }
 
     #[test]
@@ -6137,12 +7929,78 @@ mod tests {
 
     #[test]
     fn test_invalid_double_string() {
-        assert_error_expr!("\"foo", cols(4, 4));
+        assert_error_expr!("\"foo", cols(1, 1));
         assert_error_expr!("\"foo{\"", cols(6, 6));
         assert_error_expr!("\"{}\"", cols(3, 3));
         assert_error_expr!("\"foo{\"{1}\"\"", cols(11, 11));
     }
 
+    #[test]
+    fn test_double_string_with_multiple_expressions() {
+        let Expression::DoubleString(node) = expr("\"{a} and {b} \\{c}\"")
+        else {
+            panic!("expected a double quoted string");
+        };
+
+        let values: Vec<_> = node
+            .values
+            .iter()
+            .map(|v| match v {
+                DoubleStringValue::Text(n) => n.value.clone(),
+                DoubleStringValue::Expression(n) => match &n.value {
+                    Expression::Identifier(n) => format!("<{}>", n.name),
+                    _ => "<?>".to_string(),
+                },
+            })
+            .collect();
+
+        assert_eq!(values, vec!["<a>", " and ", "<b>", " {c}"]);
+    }
+
+    #[test]
+    fn test_adjacent_strings() {
+        assert_eq!(
+            expr("'foo' 'bar'"),
+            Expression::SingleString(Box::new(StringLiteral {
+                value: Some(StringText {
+                    value: "foobar".to_string(),
+                    location: cols(2, 10)
+                }),
+                location: cols(1, 11)
+            }))
+        );
+
+        let pairs = [
+            ("'' 'a' ''", "'a'"),
+            ("\"a{b}\" \"c\"", "\"a{b}c\""),
+            ("\"a\" \"{b}\" \"c\"", "\"a{b}c\""),
+            ("[\n'a'\n'b'\n]", "['a', 'b']"),
+            ("%[\n'a'\n'b'\n]", "%['a', 'b']"),
+            ("(['a'\n'b'])", "(['a', 'b'])"),
+            ("['a' 'b'\n'c']", "['ab', 'c']"),
+            ("(\"a\"\n\"b\")", "(\"ab\")"),
+            ("foo(\n'a'\n'b'\n)", "foo('ab')"),
+        ];
+
+        for (input, expected) in pairs {
+            assert!(
+                expr(input).structural_eq(&expr(expected)),
+                "{:?} should be the same as {:?}",
+                input,
+                expected
+            );
+        }
+
+        let body = match top(parse("fn a { 'a'\n'b' }")) {
+            TopLevelExpression::DefineMethod(node) => node.body.unwrap(),
+            _ => panic!("Expected a method"),
+        };
+
+        assert_eq!(body.values.len(), 2);
+        assert_error_expr!("'a' \"b\"", cols(5, 5));
+        assert_error_expr!("\"a\" 'b'", cols(5, 5));
+    }
+
     #[test]
     fn test_empty_array_expression() {
         assert_eq!(
@@ -6157,27 +8015,120 @@ mod tests {
     #[test]
     fn test_array_expression() {
         assert_eq!(
-            expr("[10, 20,]"),
-            Expression::Array(Box::new(Array {
+            expr("[10, 20,]"),
+            Expression::Array(Box::new(Array {
+                values: vec![
+                    Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(2, 3)
+                    })),
+                    Expression::Int(Box::new(IntLiteral {
+                        value: "20".to_string(),
+                        location: cols(6, 7)
+                    })),
+                ],
+                location: cols(1, 9)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_array_with_splat() {
+        assert_eq!(
+            expr("[*a, 10]"),
+            Expression::Array(Box::new(Array {
+                values: vec![
+                    Expression::Splat(Box::new(Splat {
+                        value: Expression::Identifier(Box::new(Identifier {
+                            name: "a".to_string(),
+                            location: cols(3, 3)
+                        })),
+                        location: cols(2, 3)
+                    })),
+                    Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(6, 7)
+                    })),
+                ],
+                location: cols(1, 8)
+            }))
+        );
+
+        let splats = |input: &str| {
+            let Expression::Array(node) = expr(input) else {
+                panic!("expected an array");
+            };
+
+            node.values
+                .iter()
+                .map(|v| matches!(v, Expression::Splat(_)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(splats("[10, *a, 20]"), vec![false, true, false]);
+        assert_eq!(splats("[10, 20, *a.b]"), vec![false, false, true]);
+        assert_eq!(splats("[*a, *b]"), vec![true, true]);
+    }
+
+    #[test]
+    fn test_invalid_array_with_splat() {
+        assert_error_expr!("[*]", cols(3, 3));
+        assert_error_expr!("[**a]", cols(2, 3));
+    }
+
+    #[test]
+    fn test_set_expression() {
+        assert_eq!(
+            expr("%[]"),
+            Expression::Set(Box::new(Set {
+                values: Vec::new(),
+                location: cols(1, 3)
+            }))
+        );
+
+        assert_eq!(
+            expr("%[10, 10,]"),
+            Expression::Set(Box::new(Set {
                 values: vec![
                     Expression::Int(Box::new(IntLiteral {
                         value: "10".to_string(),
-                        location: cols(2, 3)
+                        location: cols(3, 4)
                     })),
                     Expression::Int(Box::new(IntLiteral {
-                        value: "20".to_string(),
-                        location: cols(6, 7)
+                        value: "10".to_string(),
+                        location: cols(7, 8)
                     })),
                 ],
-                location: cols(1, 9)
+                location: cols(1, 10)
             }))
         );
+
+        assert!(matches!(expr("10 %[20]"), Expression::Binary(_)));
+    }
+
+    #[test]
+    fn test_invalid_set() {
+        assert_error_expr!("%[10", cols(4, 4));
+        assert_error_expr!("%10", cols(1, 1));
     }
 
     #[test]
     fn test_invalid_tuple() {
         assert_error_expr!("()", cols(2, 2));
         assert_error_expr!("(,)", cols(2, 2));
+        assert_error_expr!("((10,),", cols(7, 7));
+    }
+
+    #[test]
+    fn test_empty_tuple_error_message() {
+        let mut parser = parser("()");
+        let start = parser.require().unwrap();
+        let error = parser.expression(start).unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "Tuples must contain at least one value, such as '(10,)'"
+        );
     }
 
     #[test]
@@ -6209,6 +8160,32 @@ mod tests {
                 location: cols(1, 9)
             }))
         );
+
+        assert_eq!(
+            expr("((10,), 20)"),
+            Expression::Tuple(Box::new(Tuple {
+                values: vec![
+                    Expression::Tuple(Box::new(Tuple {
+                        values: vec![Expression::Int(Box::new(IntLiteral {
+                            value: "10".to_string(),
+                            location: cols(3, 4)
+                        }))],
+                        location: cols(2, 6)
+                    })),
+                    Expression::Int(Box::new(IntLiteral {
+                        value: "20".to_string(),
+                        location: cols(9, 10)
+                    })),
+                ],
+                location: cols(1, 11)
+            }))
+        );
+
+        let Expression::Group(group) = expr("((10))") else {
+            panic!("expected a group");
+        };
+
+        assert!(matches!(group.value, Expression::Group(_)));
     }
 
     #[test]
@@ -6586,6 +8563,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_binary_expression_spanning_multiple_lines() {
+        assert_eq!(
+            expr("1 +\n2"),
+            Expression::Binary(Box::new(Binary {
+                operator: Operator {
+                    kind: OperatorKind::Add,
+                    location: cols(3, 3)
+                },
+                left: Expression::Int(Box::new(IntLiteral {
+                    value: "1".to_string(),
+                    location: cols(1, 1)
+                })),
+                right: Expression::Int(Box::new(IntLiteral {
+                    value: "2".to_string(),
+                    location: location(2..=2, 1..=1)
+                })),
+                location: location(1..=2, 1..=1)
+            }))
+        );
+
+        assert_eq!(
+            expr("1\n+ 2"),
+            Expression::Binary(Box::new(Binary {
+                operator: Operator {
+                    kind: OperatorKind::Add,
+                    location: location(2..=2, 1..=1)
+                },
+                left: Expression::Int(Box::new(IntLiteral {
+                    value: "1".to_string(),
+                    location: cols(1, 1)
+                })),
+                right: Expression::Int(Box::new(IntLiteral {
+                    value: "2".to_string(),
+                    location: location(2..=2, 3..=3)
+                })),
+                location: location(1..=2, 1..=3)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_binary_expression_with_chained_comparisons() {
+        assert_error_expr!("a < b < c", cols(7, 7));
+        assert_error_expr!("a <= b > c", cols(8, 8));
+        assert_error_expr!("a >= b >= c", cols(8, 9));
+        assert_error_expr!("a + b < c > d", cols(11, 11));
+        assert!(matches!(expr("(a < b) < c"), Expression::Binary(_)));
+        assert!(matches!(expr("a < b == c"), Expression::Binary(_)));
+    }
+
     #[test]
     fn test_field_expression() {
         assert_eq!(
@@ -6609,6 +8637,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calls_with_constant_receivers() {
+        assert_eq!(
+            expr("Math.sqrt(2)"),
+            Expression::Call(Box::new(Call {
+                receiver: Some(Expression::Constant(Box::new(Constant {
+                    source: None,
+                    name: "Math".to_string(),
+                    location: cols(1, 4)
+                }))),
+                name: Identifier {
+                    name: "sqrt".to_string(),
+                    location: cols(6, 9)
+                },
+                arguments: Some(Arguments {
+                    values: vec![Argument::Positional(Expression::Int(
+                        Box::new(IntLiteral {
+                            value: "2".to_string(),
+                            location: cols(11, 11)
+                        })
+                    ))],
+                    location: cols(10, 12)
+                }),
+                location: cols(1, 12)
+            }))
+        );
+
+        assert_eq!(
+            expr("Foo.bar"),
+            Expression::Call(Box::new(Call {
+                receiver: Some(Expression::Constant(Box::new(Constant {
+                    source: None,
+                    name: "Foo".to_string(),
+                    location: cols(1, 3)
+                }))),
+                name: Identifier {
+                    name: "bar".to_string(),
+                    location: cols(5, 7)
+                },
+                arguments: None,
+                location: cols(1, 7)
+            }))
+        );
+    }
+
     #[test]
     fn test_identifier_expression() {
         assert_eq!(
@@ -6971,6 +9044,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_binary_assign_with_binary_value() {
+        // `a += b + c` is the same as `a = a + (b + c)`.
+        let Expression::BinaryAssignVariable(node) = expr("a += b + c") else {
+            panic!("expected a binary assignment");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Add);
+        assert!(matches!(
+            node.value,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Add
+        ));
+
+        let Expression::BinaryAssignField(node) = expr("@a *= b - c") else {
+            panic!("expected a binary assignment");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Mul);
+        assert!(matches!(
+            node.value,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Sub
+        ));
+    }
+
     #[test]
     fn test_binary_assign_field_expression() {
         assert_eq!(
@@ -7276,6 +9373,7 @@ mod tests {
                     values: vec![Argument::Positional(Expression::Closure(
                         Box::new(Closure {
                             moving: false,
+                            captures: None,
                             arguments: None,
                             return_type: None,
                             body: Expressions {
@@ -7309,6 +9407,7 @@ mod tests {
                     values: vec![Argument::Positional(Expression::Closure(
                         Box::new(Closure {
                             moving: false,
+                            captures: None,
                             arguments: None,
                             return_type: None,
                             body: Expressions {
@@ -7342,6 +9441,7 @@ mod tests {
                     values: vec![Argument::Positional(Expression::Closure(
                         Box::new(Closure {
                             moving: false,
+                            captures: None,
                             arguments: None,
                             return_type: None,
                             body: Expressions {
@@ -7358,6 +9458,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_call_with_trailing_curly_braces() {
+        assert_eq!(
+            expr("list.each { x }"),
+            Expression::Call(Box::new(Call {
+                receiver: Some(Expression::Identifier(Box::new(Identifier {
+                    name: "list".to_string(),
+                    location: cols(1, 4)
+                }))),
+                name: Identifier {
+                    name: "each".to_string(),
+                    location: cols(6, 9)
+                },
+                arguments: Some(Arguments {
+                    values: vec![Argument::Positional(Expression::Closure(
+                        Box::new(Closure {
+                            moving: false,
+                            captures: None,
+                            arguments: None,
+                            return_type: None,
+                            body: Expressions {
+                                values: vec![Expression::Identifier(Box::new(
+                                    Identifier {
+                                        name: "x".to_string(),
+                                        location: cols(13, 13)
+                                    }
+                                ))],
+                                location: cols(11, 15)
+                            },
+                            location: cols(11, 15)
+                        })
+                    ))],
+                    location: cols(11, 15)
+                }),
+                location: cols(1, 15)
+            }))
+        );
+
+        assert!(expr("list.map(1) { x }")
+            .structural_eq(&expr("list.map(1) fn { x }")));
+        assert!(expr("foo { x }").structural_eq(&expr("foo fn { x }")));
+        assert!(expr("A.new { x }").structural_eq(&expr("A.new fn { x }")));
+        assert!(matches!(expr("A { @a = 10 }"), Expression::ClassLiteral(_)));
+    }
+
+    #[test]
+    fn test_call_without_trailing_curly_braces() {
+        // A `{` on the next line isn't a trailing block.
+        let Expression::Call(call) = expr("list.each\n{ x }") else {
+            panic!("expected a call");
+        };
+
+        assert!(call.arguments.is_none());
+
+        // Trailing blocks aren't allowed in conditions.
+        let Expression::If(node) = expr("if list.empty? { x }") else {
+            panic!("expected an if expression");
+        };
+
+        assert!(matches!(
+            node.if_true.condition,
+            Expression::Call(ref call) if call.arguments.is_none()
+        ));
+    }
+
     #[test]
     fn test_call_with_trailing_blocks_with_parentheses() {
         assert_eq!(
@@ -7372,6 +9537,7 @@ mod tests {
                     values: vec![Argument::Positional(Expression::Closure(
                         Box::new(Closure {
                             moving: false,
+                            captures: None,
                             arguments: None,
                             return_type: None,
                             body: Expressions {
@@ -7408,6 +9574,7 @@ mod tests {
             node2,
             Expression::Closure(Box::new(Closure {
                 moving: false,
+                captures: None,
                 arguments: None,
                 return_type: None,
                 body: Expressions {
@@ -7419,6 +9586,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_call_with_exclamation_mark() {
+        assert_eq!(
+            expr("a.sort!"),
+            Expression::Call(Box::new(Call {
+                receiver: Some(Expression::Identifier(Box::new(Identifier {
+                    name: "a".to_string(),
+                    location: cols(1, 1)
+                }))),
+                name: Identifier {
+                    name: "sort!".to_string(),
+                    location: cols(3, 7)
+                },
+                arguments: None,
+                location: cols(1, 7)
+            }))
+        );
+
+        match expr("a.sort!= b") {
+            Expression::Binary(node) => {
+                assert_eq!(node.operator.kind, OperatorKind::Ne);
+            }
+            node => panic!("expected a binary expression, found {:?}", node),
+        }
+    }
+
     #[test]
     fn test_calls_with_receivers() {
         assert_eq!(
@@ -7574,6 +9767,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_calls_with_receivers_on_new_lines() {
+        assert_eq!(
+            expr("foo\n  .bar(10)\n  .baz"),
+            Expression::Call(Box::new(Call {
+                receiver: Some(Expression::Call(Box::new(Call {
+                    receiver: Some(Expression::Identifier(Box::new(
+                        Identifier {
+                            name: "foo".to_string(),
+                            location: cols(1, 3)
+                        }
+                    ))),
+                    name: Identifier {
+                        name: "bar".to_string(),
+                        location: location(2..=2, 4..=6)
+                    },
+                    arguments: Some(Arguments {
+                        values: vec![Argument::Positional(Expression::Int(
+                            Box::new(IntLiteral {
+                                value: "10".to_string(),
+                                location: location(2..=2, 8..=9)
+                            })
+                        ))],
+                        location: location(2..=2, 7..=10)
+                    }),
+                    location: location(1..=2, 1..=10)
+                }))),
+                name: Identifier {
+                    name: "baz".to_string(),
+                    location: location(3..=3, 4..=6)
+                },
+                arguments: None,
+                location: location(1..=3, 1..=6)
+            }))
+        );
+
+        let mut parser = parser("foo\n  .bar\nbaz");
+        let token1 = parser.require().unwrap();
+        let node1 = parser.expression(token1).unwrap();
+        let token2 = parser.require().unwrap();
+        let node2 = parser.expression(token2).unwrap();
+
+        assert!(matches!(node1, Expression::Call(_)));
+        assert_eq!(
+            node2,
+            Expression::Identifier(Box::new(Identifier {
+                name: "baz".to_string(),
+                location: location(3..=3, 1..=3)
+            }))
+        );
+    }
+
     #[test]
     fn test_invalid_calls() {
         assert_error_expr!("foo(", cols(4, 4));
@@ -7604,6 +9849,7 @@ mod tests {
             expr("fn { 10 }"),
             Expression::Closure(Box::new(Closure {
                 moving: false,
+                captures: None,
                 arguments: None,
                 return_type: None,
                 body: Expressions {
@@ -7621,6 +9867,7 @@ mod tests {
             expr("fn move { 10 }"),
             Expression::Closure(Box::new(Closure {
                 moving: true,
+                captures: None,
                 arguments: None,
                 return_type: None,
                 body: Expressions {
@@ -7638,6 +9885,7 @@ mod tests {
             expr("fn (a) { 10 }"),
             Expression::Closure(Box::new(Closure {
                 moving: false,
+                captures: None,
                 arguments: Some(BlockArguments {
                     values: vec![BlockArgument {
                         name: Identifier {
@@ -7665,6 +9913,7 @@ mod tests {
             expr("fn (a: T) { 10 }"),
             Expression::Closure(Box::new(Closure {
                 moving: false,
+                captures: None,
                 arguments: Some(BlockArguments {
                     values: vec![BlockArgument {
                         name: Identifier {
@@ -7682,48 +9931,117 @@ mod tests {
                         }))),
                         location: cols(5, 8)
                     }],
-                    location: cols(4, 9)
+                    location: cols(4, 9)
+                }),
+                return_type: None,
+                body: Expressions {
+                    values: vec![Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(13, 14)
+                    }))],
+                    location: cols(11, 16)
+                },
+                location: cols(1, 16)
+            }))
+        );
+
+        assert_eq!(
+            expr("fn -> T { 10 }"),
+            Expression::Closure(Box::new(Closure {
+                moving: false,
+                captures: None,
+                arguments: None,
+                return_type: Some(Type::Named(Box::new(TypeName {
+                    name: Constant {
+                        source: None,
+                        name: "T".to_string(),
+                        location: cols(7, 7)
+                    },
+                    arguments: None,
+                    location: cols(7, 7)
+                }))),
+                body: Expressions {
+                    values: vec![Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(11, 12)
+                    }))],
+                    location: cols(9, 14)
+                },
+                location: cols(1, 14)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_closures_with_captures() {
+        assert_eq!(
+            expr("fn move [a, b] (c) { c }"),
+            Expression::Closure(Box::new(Closure {
+                moving: true,
+                captures: Some(Captures {
+                    values: vec![
+                        Identifier {
+                            name: "a".to_string(),
+                            location: cols(10, 10)
+                        },
+                        Identifier {
+                            name: "b".to_string(),
+                            location: cols(13, 13)
+                        }
+                    ],
+                    location: cols(9, 14)
+                }),
+                arguments: Some(BlockArguments {
+                    values: vec![BlockArgument {
+                        name: Identifier {
+                            name: "c".to_string(),
+                            location: cols(17, 17)
+                        },
+                        value_type: None,
+                        location: cols(17, 17)
+                    }],
+                    location: cols(16, 18)
                 }),
                 return_type: None,
                 body: Expressions {
-                    values: vec![Expression::Int(Box::new(IntLiteral {
-                        value: "10".to_string(),
-                        location: cols(13, 14)
-                    }))],
-                    location: cols(11, 16)
+                    values: vec![Expression::Identifier(Box::new(
+                        Identifier {
+                            name: "c".to_string(),
+                            location: cols(22, 22)
+                        }
+                    ))],
+                    location: cols(20, 24)
                 },
-                location: cols(1, 16)
+                location: cols(1, 24)
             }))
         );
 
         assert_eq!(
-            expr("fn -> T { 10 }"),
+            expr("fn [] { 10 }"),
             Expression::Closure(Box::new(Closure {
                 moving: false,
+                captures: Some(Captures {
+                    values: Vec::new(),
+                    location: cols(4, 5)
+                }),
                 arguments: None,
-                return_type: Some(Type::Named(Box::new(TypeName {
-                    name: Constant {
-                        source: None,
-                        name: "T".to_string(),
-                        location: cols(7, 7)
-                    },
-                    arguments: None,
-                    location: cols(7, 7)
-                }))),
+                return_type: None,
                 body: Expressions {
                     values: vec![Expression::Int(Box::new(IntLiteral {
                         value: "10".to_string(),
-                        location: cols(11, 12)
+                        location: cols(9, 10)
                     }))],
-                    location: cols(9, 14)
+                    location: cols(7, 12)
                 },
-                location: cols(1, 14)
+                location: cols(1, 12)
             }))
         );
     }
 
     #[test]
     fn test_invalid_closures() {
+        assert_error_expr!("fn [10] {}", cols(5, 6));
+        assert_error_expr!("fn [a {}", cols(7, 7));
         assert_error_expr!("fn {", cols(4, 4));
         assert_error_expr!("fn ->", cols(5, 5));
         assert_error_expr!("fn =>", cols(4, 5));
@@ -7871,6 +10189,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_grouped_expression_overrides_precedence() {
+        let Expression::Binary(node) = expr("(a + b) * c") else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Mul);
+        assert_eq!(node.location, cols(1, 11));
+
+        let Expression::Group(group) = node.left else {
+            panic!("expected a group");
+        };
+
+        assert!(matches!(
+            group.value,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Add
+        ));
+        assert!(matches!(node.right, Expression::Identifier(_)));
+    }
+
+    #[test]
+    fn test_invalid_grouped_expression() {
+        assert_error_expr!("(10", cols(3, 3));
+        assert_error_expr!("(a + b", cols(6, 6));
+        assert_error_expr!("(a + b]", cols(7, 7));
+    }
+
     #[test]
     fn test_next_expression() {
         assert_eq!(
@@ -8049,6 +10394,7 @@ mod tests {
                     arguments: None,
                     location: cols(7, 7)
                 })),
+                is_safe: false,
                 location: cols(1, 7)
             }))
         );
@@ -8080,6 +10426,7 @@ mod tests {
                     arguments: None,
                     location: cols(11, 11)
                 })),
+                is_safe: false,
                 location: cols(1, 11)
             }))
         );
@@ -8101,6 +10448,7 @@ mod tests {
                         arguments: None,
                         location: cols(7, 7)
                     })),
+                    is_safe: false,
                     location: cols(1, 7)
                 })),
                 cast_to: Type::Named(Box::new(TypeName {
@@ -8112,9 +10460,80 @@ mod tests {
                     arguments: None,
                     location: cols(12, 12)
                 })),
+                is_safe: false,
                 location: cols(1, 12)
             }))
         );
+
+        assert_eq!(
+            expr("10 as? B"),
+            Expression::TypeCast(Box::new(TypeCast {
+                value: Expression::Int(Box::new(IntLiteral {
+                    value: "10".to_string(),
+                    location: cols(1, 2)
+                })),
+                cast_to: Type::Named(Box::new(TypeName {
+                    name: Constant {
+                        source: None,
+                        name: "B".to_string(),
+                        location: cols(8, 8)
+                    },
+                    arguments: None,
+                    location: cols(8, 8)
+                })),
+                is_safe: true,
+                location: cols(1, 8)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_ascription_expression() {
+        assert_eq!(
+            expr("10: B"),
+            Expression::Ascription(Box::new(Ascription {
+                value: Expression::Int(Box::new(IntLiteral {
+                    value: "10".to_string(),
+                    location: cols(1, 2)
+                })),
+                target_type: Type::Named(Box::new(TypeName {
+                    name: Constant {
+                        source: None,
+                        name: "B".to_string(),
+                        location: cols(5, 5)
+                    },
+                    arguments: None,
+                    location: cols(5, 5)
+                })),
+                location: cols(1, 5)
+            }))
+        );
+
+        let Expression::Call(call) = expr("foo(a: 10, b: 20: B)") else {
+            panic!("expected a call");
+        };
+        let args = call.arguments.unwrap().values;
+
+        assert!(matches!(
+            &args[0],
+            Argument::Named(arg) if matches!(arg.value, Expression::Int(_))
+        ));
+        assert!(matches!(
+            &args[1],
+            Argument::Named(arg)
+                if matches!(arg.value, Expression::Ascription(_))
+        ));
+
+        let Expression::Call(call) = expr("foo(10: B)") else {
+            panic!("expected a call");
+        };
+
+        assert!(matches!(
+            &call.arguments.unwrap().values[0],
+            Argument::Positional(Expression::Ascription(_))
+        ));
+
+        assert_error_expr!("10:", cols(3, 3));
     }
 
     #[test]
@@ -8167,6 +10586,7 @@ mod tests {
             Expression::Return(Box::new(Return {
                 value: Some(Expression::Closure(Box::new(Closure {
                     moving: false,
+                    captures: None,
                     arguments: None,
                     return_type: None,
                     body: Expressions {
@@ -8605,6 +11025,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_if_expression_as_value() {
+        let Expression::DefineVariable(def) =
+            expr("let x = if a { 1 } else if b { if c { 2 } } else { 3 }")
+        else {
+            panic!("expected a variable definition");
+        };
+        let Expression::If(node) = def.value else {
+            panic!("expected an if expression");
+        };
+
+        assert_eq!(node.else_if.len(), 1);
+        assert!(node.else_body.is_some());
+        assert!(matches!(node.else_if[0].body.values[0], Expression::If(_)));
+    }
+
     #[test]
     fn test_invalid_if_expressions() {
         assert_error_expr!("if foo { b } else if", cols(20, 20));
@@ -8642,6 +11078,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_power_is_right_associative() {
+        let Expression::Binary(node) = expr("2 ** 3 ** 2") else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Pow);
+        assert_eq!(node.operator.location, cols(3, 4));
+        assert!(matches!(node.left, Expression::Int(_)));
+        assert_eq!(node.location, cols(1, 11));
+
+        let Expression::Binary(rhs) = node.right else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(rhs.operator.kind, OperatorKind::Pow);
+        assert_eq!(rhs.location, cols(6, 11));
+    }
+
+    #[test]
+    fn test_power_with_other_operators() {
+        // `**` only groups with other `**` operators on its right-hand side.
+        let Expression::Binary(node) = expr("1 + 2 ** 3 ** 2") else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Pow);
+        assert!(matches!(
+            node.left,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Add
+        ));
+        assert!(matches!(
+            node.right,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Pow
+        ));
+
+        let Expression::Binary(node) = expr("2 ** 3 + 1") else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Add);
+        assert!(matches!(
+            node.left,
+            Expression::Binary(ref n) if n.operator.kind == OperatorKind::Pow
+        ));
+
+        // Negative literals are a single value, so this is `(-2) ** 2`.
+        let Expression::Binary(node) = expr("-2 ** 2") else {
+            panic!("expected a binary expression");
+        };
+
+        assert_eq!(node.operator.kind, OperatorKind::Pow);
+        assert!(matches!(node.left, Expression::Int(ref n) if n.value == "-2"));
+    }
+
+    #[test]
+    fn test_match_char_pattern() {
+        let Expression::Match(node) = expr("match 1 { case ?a -> 2 }") else {
+            panic!("expected a match expression");
+        };
+
+        assert_eq!(
+            node.cases[0].pattern,
+            Pattern::Expression(Box::new(Expression::Char(Box::new(
+                CharLiteral { value: 'a', location: cols(16, 17) }
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_match_with_guard() {
+        assert_eq!(
+            expr("match 1 { case v if v > 2 -> 3 }"),
+            Expression::Match(Box::new(Match {
+                expression: Expression::Int(Box::new(IntLiteral {
+                    value: "1".to_string(),
+                    location: cols(7, 7)
+                })),
+                cases: vec![MatchCase {
+                    pattern: Pattern::Identifier(Box::new(IdentifierPattern {
+                        name: Identifier {
+                            name: "v".to_string(),
+                            location: cols(16, 16)
+                        },
+                        mutable: false,
+                        value_type: None,
+                        location: cols(16, 16)
+                    })),
+                    guard: Some(Expression::Binary(Box::new(Binary {
+                        left: Expression::Identifier(Box::new(Identifier {
+                            name: "v".to_string(),
+                            location: cols(21, 21)
+                        })),
+                        right: Expression::Int(Box::new(IntLiteral {
+                            value: "2".to_string(),
+                            location: cols(25, 25)
+                        })),
+                        operator: Operator {
+                            kind: OperatorKind::Gt,
+                            location: cols(23, 23)
+                        },
+                        location: cols(21, 25)
+                    }))),
+                    body: Expressions {
+                        values: vec![Expression::Int(Box::new(IntLiteral {
+                            value: "3".to_string(),
+                            location: cols(30, 30)
+                        }))],
+                        location: cols(30, 30)
+                    },
+                    location: cols(11, 30)
+                }],
+                location: cols(1, 32)
+            }))
+        );
+    }
+
     #[test]
     fn test_match_or_pattern() {
         assert_eq!(
@@ -9225,9 +11778,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_while_expression_with_binary_condition() {
+        assert!(matches!(
+            expr("while x < 10 { x = x + 1 }"),
+            Expression::While(node) if matches!(
+                (&node.condition, &node.body.values[0]),
+                (Expression::Binary(_), Expression::AssignVariable(_))
+            )
+        ));
+        assert!(matches!(
+            expr("while (foo.bar) { 10 }"),
+            Expression::While(node) if matches!(node.condition, Expression::Group(_))
+        ));
+    }
+
     #[test]
     fn test_invalid_while_expression() {
         assert_error_expr!("while 10 20 }", cols(10, 11));
+        assert_error_expr!("while { 10 }", cols(7, 7));
     }
 
     #[test]
@@ -9235,6 +11804,7 @@ mod tests {
         assert_eq!(
             top(parse("class enum Option[T] { case Some(T) case None }")),
             TopLevelExpression::DefineClass(Box::new(DefineClass {
+                documentation: None,
                 public: false,
                 kind: ClassKind::Enum,
                 name: Constant {
@@ -9320,4 +11890,259 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn test_structural_eq() {
+        let loc = location(1..=1, 1..=1);
+
+        assert!(expr("10 + 20").structural_eq(&expr("10  +  20")));
+        assert!(expr("[10]").structural_eq(&expr("[\n  10\n]")));
+        assert!(expr("a.b(10)").structural_eq(&expr("a\n  .b(10)")));
+        assert!(expr("10").structural_eq(&Expression::Int(Box::new(
+            IntLiteral { value: "10".to_string(), location: loc.clone() }
+        ))));
+
+        assert!(!expr("10 + 20").structural_eq(&expr("10 + 30")));
+        assert!(!expr("10 + 20").structural_eq(&expr("10 - 20")));
+        assert!(!expr("[10]").structural_eq(&expr("[10, 20]")));
+
+        // Regular comparisons still take the locations into account.
+        assert_ne!(expr("10 + 20"), expr("10  +  20"));
+        assert_ne!(cols(1, 2), loc);
+    }
+
+    #[test]
+    fn test_do_end_blocks() {
+        let pairs = [
+            ("fn a do 10 end", "fn a { 10 }"),
+            ("fn a -> Int do 10 end", "fn a -> Int { 10 }"),
+            ("class A do fn a do 10 end end", "class A { fn a { 10 } }"),
+            ("class A do let @a: Int end", "class A { let @a: Int }"),
+            ("impl A do fn a do 10 end end", "impl A { fn a { 10 } }"),
+            ("impl A for B do fn a do end end", "impl A for B { fn a {} }"),
+            (
+                "trait A do fn a fn b do 10 end end",
+                "trait A { fn a fn b { 10 } }",
+            ),
+            ("fn a { fn do 10 end }", "fn a { fn { 10 } }"),
+            ("fn a { fn (a) do a end }", "fn a { fn (a) { a } }"),
+            ("fn a { loop do 10 end }", "fn a { loop { 10 } }"),
+            ("fn a { while a do 10 end }", "fn a { while a { 10 } }"),
+            (
+                "fn a { if a do 1 end else if b do 2 end else do 3 end }",
+                "fn a { if a { 1 } else if b { 2 } else { 3 } }",
+            ),
+            ("fn a do fn { 10 } end", "fn a { fn { 10 } }"),
+        ];
+
+        for (input, expected) in pairs {
+            assert!(
+                parse(input).structural_eq(&parse(expected)),
+                "{:?} should be the same as {:?}",
+                input,
+                expected
+            );
+        }
+
+        assert_eq!(
+            top(parse("fn a do 10 end")),
+            TopLevelExpression::DefineMethod(Box::new(DefineMethod {
+                documentation: None,
+                public: false,
+                operator: false,
+                name: Identifier {
+                    name: "a".to_string(),
+                    location: cols(4, 4)
+                },
+                type_parameters: None,
+                arguments: None,
+                return_type: None,
+                body: Some(Expressions {
+                    values: vec![Expression::Int(Box::new(IntLiteral {
+                        value: "10".to_string(),
+                        location: cols(9, 10)
+                    }))],
+                    location: cols(6, 14)
+                }),
+                location: cols(1, 14),
+                kind: MethodKind::Instance,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_do_and_end_as_identifiers() {
+        let body = match top(parse("fn a { let end = do\nend }")) {
+            TopLevelExpression::DefineMethod(node) => node.body.unwrap(),
+            _ => panic!("Expected a method"),
+        };
+
+        assert!(matches!(
+            &body.values[1],
+            Expression::Identifier(n) if n.name == "end"
+        ));
+
+        assert!(parse("fn a do let x = end.do end")
+            .structural_eq(&parse("fn a { let x = end.do }")));
+        assert!(parse("fn a do\n  let end = 10\n  foo(end)\nend")
+            .structural_eq(&parse("fn a {\n  let end = 10\n  foo(end)\n}")));
+
+        // In a block opened using `do`, an expression starting with `end`
+        // closes the block instead.
+        assert!(parser("fn a do\n  let end = 10\n  end + 1\nend")
+            .parse()
+            .is_err());
+        assert!(parser("fn a do\n  let end = 10\n  (end + 1)\nend")
+            .parse()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_do_end_blocks() {
+        assert_error!("fn a do 10 }", cols(12, 12));
+        assert_error!("fn a 10", cols(6, 7));
+        assert_error!("class A do fn a {} }", cols(20, 20));
+        assert_error!("fn a { loop do 10 } }", cols(19, 19));
+    }
+
+    #[test]
+    fn test_parser_with_origin() {
+        let origin = Some(Arc::new(PathBuf::from("foo.inko")));
+        let ast = Parser::with_origin(
+            "fn a { 10 }".into(),
+            "test.inko".into(),
+            "foo.inko".into(),
+        )
+        .parse()
+        .unwrap();
+        let method = match top(ast) {
+            TopLevelExpression::DefineMethod(node) => node,
+            _ => panic!("Expected a method"),
+        };
+
+        assert_eq!(method.location.origin, origin);
+        assert_eq!(method.name.location.origin, origin);
+        assert_eq!(method.body.unwrap().values[0].location().origin, origin);
+        assert_eq!(parse("fn a { 10 }").location.origin, None);
+    }
+
+    #[test]
+    fn test_newlines_inside_brackets() {
+        let pairs = [
+            ("(foo\n(10))", "(foo(10))"),
+            ("(foo\n(10), 20)", "(foo(10), 20)"),
+            ("bar(foo\n(10))", "bar(foo(10))"),
+            ("bar(foo\nfn { 10 })", "bar(foo fn { 10 })"),
+            ("bar([10], A\n{ @a = 10 })", "bar([10], A { @a = 10 })"),
+            ("(return\n10)", "(return 10)"),
+            ("[foo(\n10\n)]", "[foo(10)]"),
+        ];
+
+        for (input, expected) in pairs {
+            assert!(
+                expr(input).structural_eq(&expr(expected)),
+                "{:?} should be the same as {:?}",
+                input,
+                expected
+            );
+        }
+
+        // Blocks inside brackets still treat newlines as significant.
+        let closure = match expr("[fn { foo\n(10) }]") {
+            Expression::Array(node) => match node.values.into_iter().next() {
+                Some(Expression::Closure(node)) => node,
+                _ => panic!("Expected a closure"),
+            },
+            _ => panic!("Expected an array"),
+        };
+
+        assert_eq!(closure.body.values.len(), 2);
+
+        // Array and set values don't need commas, so a newline still
+        // separates two values, even if the literal is inside parentheses.
+        for input in ["[foo\n(10)]", "([foo\n(10)])", "bar([foo\n(10)])"] {
+            let values = match expr(input) {
+                Expression::Array(node) => node.values,
+                Expression::Group(node) => match node.value {
+                    Expression::Array(node) => node.values,
+                    _ => panic!("Expected an array"),
+                },
+                Expression::Call(node) => {
+                    match node.arguments.unwrap().values.remove(0) {
+                        Argument::Positional(Expression::Array(node)) => {
+                            node.values
+                        }
+                        _ => panic!("Expected an array"),
+                    }
+                }
+                _ => panic!("Expected an array"),
+            };
+
+            assert_eq!(values.len(), 2, "{:?} should have two values", input);
+        }
+
+        let values = match expr("[A\n{ @a = 10 }]") {
+            Expression::Array(node) => node.values,
+            _ => panic!("Expected an array"),
+        };
+
+        assert!(matches!(values[0], Expression::Constant(_)));
+        assert!(matches!(values[1], Expression::Scope(_)));
+
+        match expr("%[foo\n(10)]") {
+            Expression::Set(node) => assert_eq!(node.values.len(), 2),
+            _ => panic!("Expected a set"),
+        }
+
+        let body = match top(parse("fn a { [10]\nfoo\n(10) }")) {
+            TopLevelExpression::DefineMethod(node) => node.body.unwrap(),
+            _ => panic!("Expected a method"),
+        };
+
+        assert_eq!(body.values.len(), 3);
+    }
+
+    #[test]
+    fn test_check_balanced() {
+        assert!(parser("").check_balanced().is_ok());
+        assert!(parser("fn a { [(10), {}] }").check_balanced().is_ok());
+        assert!(parser("'{'").check_balanced().is_ok());
+        assert!(parser("\"{[1].get(0)}\"").check_balanced().is_ok());
+
+        let error = |input: &str| {
+            let err = parser(input).check_balanced().unwrap_err();
+
+            (err.message, err.location)
+        };
+
+        assert_eq!(
+            error("fn a { (10 }"),
+            (
+                "Expected the '(' on line 1, column 8 to be closed, \
+                found '}' instead"
+                    .to_string(),
+                cols(12, 12)
+            )
+        );
+        assert_eq!(
+            error("fn a {\n  10\n}\n}"),
+            (
+                "This '}' doesn't have a matching opening '{'".to_string(),
+                location(4..=4, 1..=1)
+            )
+        );
+        assert_eq!(
+            error("fn a {\n  [10\n}"),
+            (
+                "Expected the '[' on line 2, column 3 to be closed, \
+                found '}' instead"
+                    .to_string(),
+                location(3..=3, 1..=1)
+            )
+        );
+        assert_eq!(
+            error("fn a {\n  if b {\n    10\n  }"),
+            ("This '{' is never closed".to_string(), cols(6, 6))
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::fmt;
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 // The location of a single Inko expression.
 #[derive(PartialEq, Eq, Clone)]
@@ -10,6 +12,13 @@ pub struct SourceLocation {
 
     /// The first and last column of the expression.
     pub column_range: RangeInclusive<usize>,
+
+    /// The file the expression originates from, if this differs from the
+    /// module that contains the expression.
+    ///
+    /// This is used for code parsed from one file but included in the module
+    /// of another file, such that diagnostics point to the correct file.
+    pub origin: Option<Arc<PathBuf>>,
 }
 
 impl SourceLocation {
@@ -17,7 +26,7 @@ impl SourceLocation {
         line_range: RangeInclusive<usize>,
         column_range: RangeInclusive<usize>,
     ) -> Self {
-        Self { line_range, column_range }
+        Self { line_range, column_range, origin: None }
     }
 
     pub fn start_end(start: &Self, end: &Self) -> Self {
@@ -25,9 +34,15 @@ impl SourceLocation {
             line_range: (*start.line_range.start())..=(*end.line_range.end()),
             column_range: (*start.column_range.start())
                 ..=(*end.column_range.end()),
+            origin: start.origin.clone(),
         }
     }
 
+    pub fn with_origin(mut self, origin: Option<Arc<PathBuf>>) -> Self {
+        self.origin = origin;
+        self
+    }
+
     pub fn line_column(&self) -> (usize, usize) {
         (*self.line_range.start(), *self.column_range.start())
     }
@@ -42,7 +57,13 @@ impl fmt::Debug for SourceLocation {
             self.line_range.end(),
             self.column_range.start(),
             self.column_range.end()
-        )
+        )?;
+
+        if let Some(origin) = self.origin.as_ref() {
+            write!(f, " in {}", origin.display())?;
+        }
+
+        Ok(())
     }
 }
 
@@ -98,6 +98,10 @@ impl Diagnostic {
         file: PathBuf,
         location: SourceLocation,
     ) -> Self {
+        // Code included from another file reports diagnostics for that file,
+        // not the module it's included in.
+        let file = location.origin.as_ref().map_or(file, |f| (**f).clone());
+
         Self { kind, id, message, file, location }
     }
 
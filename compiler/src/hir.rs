@@ -8,10 +8,12 @@ use crate::modules_parser::ParsedModule;
 use crate::state::State;
 use ::ast::nodes::{self as ast, Node as _};
 use ::ast::source_location::SourceLocation;
+use std::num::IntErrorKind;
 use std::path::PathBuf;
 use std::str::FromStr;
 use types::{
-    ARRAY_INTERNAL_NAME, ARRAY_LIMIT, ARRAY_PUSH, ARRAY_WITH_CAPACITY,
+    ARRAY_APPEND, ARRAY_INTERNAL_NAME, ARRAY_LIMIT, ARRAY_PUSH,
+    ARRAY_WITH_CAPACITY,
 };
 
 const BUILTIN_RECEIVER: &str = "_INKO";
@@ -469,6 +471,7 @@ pub(crate) enum Expression {
     Nil(Box<Nil>),
     Tuple(Box<TupleLiteral>),
     TypeCast(Box<TypeCast>),
+    Ascription(Box<Ascription>),
     Recover(Box<Recover>),
     Try(Box<Try>),
 }
@@ -509,6 +512,7 @@ impl Expression {
             Expression::Nil(ref n) => &n.location,
             Expression::Tuple(ref n) => &n.location,
             Expression::TypeCast(ref n) => &n.location,
+            Expression::Ascription(ref n) => &n.location,
             Expression::Recover(ref n) => &n.location,
             Expression::Try(ref n) => &n.location,
         }
@@ -867,6 +871,14 @@ pub(crate) struct TypeCast {
     pub(crate) location: SourceLocation,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Ascription {
+    pub(crate) resolved_type: types::TypeRef,
+    pub(crate) value: Expression,
+    pub(crate) target_type: Type,
+    pub(crate) location: SourceLocation,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct Throw {
     pub(crate) resolved_type: types::TypeRef,
@@ -1212,9 +1224,24 @@ impl<'a> LowerToHir<'a> {
         &mut self,
         node: ast::ClassExpressions,
     ) -> Vec<ClassExpression> {
-        node.values
-            .into_iter()
-            .map(|n| match n {
+        let mut values = Vec::new();
+
+        self.flatten_class_expressions(node, &mut values);
+        values
+    }
+
+    /// Lowers the expressions of a class body, moving the members of any
+    /// sections into the class body itself.
+    ///
+    /// The parser already applies the modifier of a section to its members,
+    /// so sections don't need any further processing.
+    fn flatten_class_expressions(
+        &mut self,
+        node: ast::ClassExpressions,
+        values: &mut Vec<ClassExpression>,
+    ) {
+        for expr in node.values {
+            let value = match expr {
                 ast::ClassExpression::DefineMethod(node) => {
                     self.define_method_in_class(*node)
                 }
@@ -1224,8 +1251,14 @@ impl<'a> LowerToHir<'a> {
                 ast::ClassExpression::DefineVariant(node) => {
                     self.define_case(*node)
                 }
-            })
-            .collect()
+                ast::ClassExpression::Section(node) => {
+                    self.flatten_class_expressions(node.body, values);
+                    continue;
+                }
+            };
+
+            values.push(value);
+        }
     }
 
     fn define_field(&self, node: ast::DefineField) -> DefineField {
@@ -1702,6 +1735,9 @@ impl<'a> LowerToHir<'a> {
             ast::Expression::Float(node) => {
                 ConstExpression::Float(self.float_literal(*node))
             }
+            ast::Expression::Char(node) => {
+                ConstExpression::Int(Box::new(self.char_literal(*node)))
+            }
             ast::Expression::SingleString(node) => {
                 ConstExpression::String(self.const_single_string_literal(*node))
             }
@@ -1744,25 +1780,46 @@ impl<'a> LowerToHir<'a> {
             input = input.replace('_', "");
         }
 
-        let hex_prefix = if input.starts_with('-') { "-0x" } else { "0x" };
-        let result = if let Some(slice) = input.strip_prefix(hex_prefix) {
-            i64::from_str_radix(slice, 16).map(|v| {
-                if input.starts_with('-') {
-                    0_i64.wrapping_sub(v)
-                } else {
-                    v
-                }
-            })
-        } else {
+        let (negative, unsigned) = match input.strip_prefix('-') {
+            Some(slice) => (true, slice),
+            None => (false, input.as_str()),
+        };
+        let radix = match unsigned.get(0..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+        let result = if radix == 10 {
             i64::from_str(&input)
+        } else if negative {
+            i64::from_str_radix(&format!("-{}", &unsigned[2..]), radix)
+        } else {
+            i64::from_str_radix(&unsigned[2..], radix)
         };
 
         let value = match result {
             Ok(val) => val,
             Err(e) => {
+                let message = match e.kind() {
+                    IntErrorKind::PosOverflow => format!(
+                        "the Int literal {} is too large, the maximum value \
+                        is {}",
+                        node.value,
+                        i64::MAX
+                    ),
+                    IntErrorKind::NegOverflow => format!(
+                        "the Int literal {} is too small, the minimum value \
+                        is {}",
+                        node.value,
+                        i64::MIN
+                    ),
+                    _ => format!("this Int literal is invalid: {}", e),
+                };
+
                 self.state.diagnostics.error(
                     DiagnosticId::InvalidSyntax,
-                    format!("this Int literal is invalid: {}", e),
+                    message,
                     self.file(),
                     node.location.clone(),
                 );
@@ -1778,6 +1835,16 @@ impl<'a> LowerToHir<'a> {
         }
     }
 
+    /// Character literals are integers using the Unicode code point of the
+    /// character as their value.
+    fn char_literal(&self, node: ast::CharLiteral) -> IntLiteral {
+        IntLiteral {
+            value: node.value as i64,
+            resolved_type: types::TypeRef::Unknown,
+            location: node.location,
+        }
+    }
+
     fn float_literal(&mut self, node: ast::FloatLiteral) -> Box<FloatLiteral> {
         let mut input = node.value;
 
@@ -1864,6 +1931,20 @@ impl<'a> LowerToHir<'a> {
         }))
     }
 
+    fn set_literal(&mut self, node: ast::Set) -> Expression {
+        self.state.diagnostics.error(
+            DiagnosticId::InvalidSyntax,
+            "set literals aren't supported yet",
+            self.file(),
+            node.location.clone(),
+        );
+
+        self.array_literal(ast::Array {
+            values: node.values,
+            location: node.location,
+        })
+    }
+
     fn array_literal(&mut self, node: ast::Array) -> Expression {
         if node.values.len() > ARRAY_LIMIT {
             self.state.diagnostics.error(
@@ -1877,6 +1958,11 @@ impl<'a> LowerToHir<'a> {
             );
         }
 
+        let capacity = node
+            .values
+            .iter()
+            .filter(|v| !matches!(v, ast::Expression::Splat(_)))
+            .count();
         let def_var = Expression::DefineVariable(Box::new(DefineVariable {
             resolved_type: types::TypeRef::Unknown,
             variable_id: None,
@@ -1903,7 +1989,7 @@ impl<'a> LowerToHir<'a> {
                 },
                 arguments: vec![Argument::Positional(Box::new(
                     Expression::Int(Box::new(IntLiteral {
-                        value: node.values.len() as _,
+                        value: capacity as _,
                         resolved_type: types::TypeRef::Unknown,
                         location: node.location.clone(),
                     })),
@@ -1922,13 +2008,20 @@ impl<'a> LowerToHir<'a> {
         let mut body = vec![def_var];
 
         for n in node.values {
-            let arg = self.expression(n);
+            // The values of a splat (e.g. `*other`) are appended to the array,
+            // instead of the splat being pushed as a single value.
+            let (name, arg) = match n {
+                ast::Expression::Splat(n) => {
+                    (ARRAY_APPEND, self.expression(n.value))
+                }
+                n => (ARRAY_PUSH, self.expression(n)),
+            };
             let loc = arg.location().clone();
             let push = Expression::Call(Box::new(Call {
                 kind: types::CallKind::Unknown,
                 receiver: Some(var_ref.clone()),
                 name: Identifier {
-                    name: ARRAY_PUSH.to_string(),
+                    name: name.to_string(),
                     location: node.location.clone(),
                 },
                 arguments: vec![Argument::Positional(Box::new(arg))],
@@ -2083,6 +2176,9 @@ impl<'a> LowerToHir<'a> {
             ast::Expression::Float(node) => {
                 Expression::Float(self.float_literal(*node))
             }
+            ast::Expression::Char(node) => {
+                Expression::Int(Box::new(self.char_literal(*node)))
+            }
             ast::Expression::Binary(node) => {
                 Expression::Call(self.binary(*node))
             }
@@ -2163,6 +2259,12 @@ impl<'a> LowerToHir<'a> {
             ast::Expression::TypeCast(node) => {
                 Expression::TypeCast(self.type_cast(*node))
             }
+            ast::Expression::Ascription(node) => {
+                Expression::Ascription(self.ascription(*node))
+            }
+            ast::Expression::Splat(_) => {
+                unreachable!("splats are only valid in array literals")
+            }
             ast::Expression::Throw(node) => {
                 Expression::Throw(self.throw_expression(*node))
             }
@@ -2189,6 +2291,7 @@ impl<'a> LowerToHir<'a> {
                 Expression::ClassLiteral(self.instance_literal(*node))
             }
             ast::Expression::Array(node) => self.array_literal(*node),
+            ast::Expression::Set(node) => self.set_literal(*node),
             ast::Expression::Tuple(node) => {
                 Expression::Tuple(self.tuple_literal(*node))
             }
@@ -2487,6 +2590,15 @@ impl<'a> LowerToHir<'a> {
     }
 
     fn closure(&mut self, node: ast::Closure) -> Box<Closure> {
+        if let Some(captures) = node.captures {
+            self.state.diagnostics.error(
+                DiagnosticId::InvalidSyntax,
+                "explicit closure captures aren't supported yet",
+                self.file(),
+                captures.location,
+            );
+        }
+
         Box::new(Closure {
             closure_id: None,
             resolved_type: types::TypeRef::Unknown,
@@ -2615,7 +2727,25 @@ impl<'a> LowerToHir<'a> {
         })
     }
 
+    fn ascription(&mut self, node: ast::Ascription) -> Box<Ascription> {
+        Box::new(Ascription {
+            resolved_type: types::TypeRef::Unknown,
+            value: self.expression(node.value),
+            target_type: self.type_reference(node.target_type),
+            location: node.location,
+        })
+    }
+
     fn type_cast(&mut self, node: ast::TypeCast) -> Box<TypeCast> {
+        if node.is_safe {
+            self.state.diagnostics.error(
+                DiagnosticId::InvalidSyntax,
+                "safe type casts ('as?') aren't supported yet",
+                self.file(),
+                node.location.clone(),
+            );
+        }
+
         Box::new(TypeCast {
             resolved_type: types::TypeRef::Unknown,
             value: self.expression(node.value),
@@ -2854,6 +2984,9 @@ impl<'a> LowerToHir<'a> {
                 ast::Expression::Int(n) => {
                     Pattern::Int(Box::new(self.int_literal(*n)))
                 }
+                ast::Expression::Char(n) => {
+                    Pattern::Int(Box::new(self.char_literal(*n)))
+                }
                 ast::Expression::True(n) => {
                     Pattern::True(self.true_literal(*n))
                 }
@@ -3708,6 +3841,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lower_class_with_sections() {
+        let hir = lower_top_expr(
+            "class A { let @a: A\npub { static { fn b {} }\nfn c {} } }",
+        )
+        .0;
+        let body = match hir {
+            TopLevelExpression::Class(node) => node.body,
+            _ => panic!("Expected a class"),
+        };
+
+        assert_eq!(body.len(), 3);
+        assert!(matches!(
+            &body[0],
+            ClassExpression::Field(n) if !n.public
+        ));
+        assert!(matches!(
+            &body[1],
+            ClassExpression::StaticMethod(n) if n.public && n.name.name == "b"
+        ));
+        assert!(matches!(
+            &body[2],
+            ClassExpression::InstanceMethod(n) if n.public && n.name.name == "c"
+        ));
+    }
+
     #[test]
     fn test_lower_class_with_static_method() {
         let hir =
@@ -4724,6 +4883,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lower_octal_int() {
+        let hir = lower_expr("fn a { 0o755 }").0;
+
+        assert_eq!(
+            hir,
+            Expression::Int(Box::new(IntLiteral {
+                value: 0o755,
+                resolved_type: types::TypeRef::Unknown,
+                location: cols(8, 12)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_lower_negative_binary_int() {
+        let hir = lower_expr("fn a { -0b1010 }").0;
+
+        assert_eq!(
+            hir,
+            Expression::Int(Box::new(IntLiteral {
+                value: -0b1010,
+                resolved_type: types::TypeRef::Unknown,
+                location: cols(8, 14)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_lower_int_limits() {
+        let max = lower_expr("fn a { 9_223_372_036_854_775_807 }").0;
+        let min = lower_expr("fn a { -9_223_372_036_854_775_808 }").0;
+        let hex_min = lower_expr("fn a { -0x8000000000000000 }").0;
+
+        assert!(matches!(max, Expression::Int(n) if n.value == i64::MAX));
+        assert!(matches!(min, Expression::Int(n) if n.value == i64::MIN));
+        assert!(matches!(hex_min, Expression::Int(n) if n.value == i64::MIN));
+    }
+
+    #[test]
+    fn test_lower_int_overflow() {
+        let messages = |input: &str| {
+            let mut state = State::new(Config::new());
+            let ast = parse(input);
+
+            LowerToHir::run_all(&mut state, vec![ast]);
+            state
+                .diagnostics
+                .iter()
+                .map(|d| d.message().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            messages("fn a { 9223372036854775808 }"),
+            vec!["the Int literal 9223372036854775808 is too large, the \
+                maximum value is 9223372036854775807"
+                .to_string()]
+        );
+        assert_eq!(
+            messages("fn a { -9223372036854775809 }"),
+            vec!["the Int literal -9223372036854775809 is too small, the \
+                minimum value is -9223372036854775808"
+                .to_string()]
+        );
+        assert_eq!(
+            messages("fn a { 0x1_0000_0000_0000_0000 }"),
+            vec!["the Int literal 0x1_0000_0000_0000_0000 is too large, the \
+                maximum value is 9223372036854775807"
+                .to_string()]
+        );
+    }
+
+    #[test]
+    fn test_lower_invalid_binary_int() {
+        let (_, diags) = lower_expr("fn a { 0b12 }");
+
+        assert_eq!(diags, 1);
+    }
+
+    #[test]
+    fn test_lower_char() {
+        let hir = lower_expr("fn a { ?\\u{1F600} }").0;
+
+        assert_eq!(
+            hir,
+            Expression::Int(Box::new(IntLiteral {
+                value: 0x1F600,
+                resolved_type: types::TypeRef::Unknown,
+                location: cols(8, 17)
+            }))
+        );
+    }
+
     #[test]
     fn test_lower_float() {
         let hir = lower_expr("fn a { 1_0.5 }").0;
@@ -4886,6 +5139,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lower_array_with_splat() {
+        let Expression::Scope(scope) = lower_expr("fn a { [*b, 10] }").0 else {
+            panic!("expected a scope");
+        };
+        let Expression::DefineVariable(var) = &scope.body[0] else {
+            panic!("expected a variable definition");
+        };
+        let names = scope.body[1..3]
+            .iter()
+            .map(|n| match n {
+                Expression::Call(call) => call.name.name.as_str(),
+                _ => panic!("expected a call"),
+            })
+            .collect::<Vec<_>>();
+
+        let Expression::Call(new) = &var.value else {
+            panic!("expected a call");
+        };
+        let Argument::Positional(capacity) = &new.arguments[0] else {
+            panic!("expected a positional argument");
+        };
+
+        assert!(
+            matches!(capacity.as_ref(), Expression::Int(n) if n.value == 1)
+        );
+        assert_eq!(names, vec!["append", "push"]);
+    }
+
     #[test]
     fn test_lower_tuple() {
         let hir = lower_expr("fn a { (10,) }").0;
@@ -5685,6 +5967,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lower_ascription() {
+        let hir = lower_expr("fn a { 10: T }").0;
+
+        assert_eq!(
+            hir,
+            Expression::Ascription(Box::new(Ascription {
+                resolved_type: types::TypeRef::Unknown,
+                value: Expression::Int(Box::new(IntLiteral {
+                    value: 10,
+                    resolved_type: types::TypeRef::Unknown,
+                    location: cols(8, 9)
+                })),
+                target_type: Type::Named(Box::new(TypeName {
+                    source: None,
+                    resolved_type: types::TypeRef::Unknown,
+                    name: Constant {
+                        name: "T".to_string(),
+                        location: cols(12, 12)
+                    },
+                    arguments: Vec::new(),
+                    location: cols(12, 12)
+                })),
+                location: cols(8, 12),
+            }))
+        );
+    }
+
     #[test]
     fn test_lower_throw_expression() {
         let hir = lower_expr("fn a { throw 10 }").0;
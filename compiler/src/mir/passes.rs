@@ -1430,6 +1430,7 @@ impl<'a> LowerMethod<'a> {
             hir::Expression::Nil(n) => self.nil_literal(*n),
             hir::Expression::Tuple(n) => self.tuple_literal(*n),
             hir::Expression::TypeCast(n) => self.type_cast(*n),
+            hir::Expression::Ascription(n) => self.expression(n.value),
             hir::Expression::Recover(n) => self.recover_expression(*n),
             hir::Expression::Try(n) => self.try_expression(*n),
         }
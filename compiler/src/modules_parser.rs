@@ -1,16 +1,34 @@
 //! Parsing of Inko source code into ASTs.
 use crate::diagnostics::DiagnosticId;
 use crate::state::{BuildTags, State};
-use ast::nodes::{Module, Node, TopLevelExpression};
+use ast::nodes::{Identifier, ImportPath, Module, Node, TopLevelExpression};
 use ast::parser::Parser;
 use ast::source_location::SourceLocation;
 use std::collections::{HashMap, HashSet};
 use std::fs::read;
 use std::path::PathBuf;
-use types::module_name::ModuleName;
+use types::module_name::{ModuleName, SEPARATOR};
+
+/// Replaces the leading `self` of a relative import path with the name of the
+/// importing module, turning `self.foo` in module `a.b` into `a.b.foo`.
+fn expand_relative_path(path: &mut ImportPath, module: &ModuleName) {
+    let location = path.steps[0].location.clone();
+    let mut steps: Vec<_> = module
+        .as_str()
+        .split(SEPARATOR)
+        .map(|name| Identifier {
+            name: name.to_string(),
+            location: location.clone(),
+        })
+        .collect();
+
+    steps.extend(path.steps.drain(1..));
+    path.steps = steps;
+}
 
 fn imported_modules(
     module: &mut Module,
+    module_name: &ModuleName,
     tags: &BuildTags,
 ) -> Vec<(ModuleName, SourceLocation)> {
     let mut names = Vec::new();
@@ -26,6 +44,10 @@ fn imported_modules(
                     continue;
                 }
 
+                if node.path.is_relative() {
+                    expand_relative_path(&mut node.path, module_name);
+                }
+
                 (&node.path, node.location().clone())
             }
             _ => continue,
@@ -88,7 +110,8 @@ impl<'a> ModulesParser<'a> {
 
         while let Some((qname, file)) = pending.pop() {
             if let Some(mut ast) = self.parse(&file) {
-                let deps = imported_modules(&mut ast, &self.state.build_tags);
+                let deps =
+                    imported_modules(&mut ast, &qname, &self.state.build_tags);
 
                 modules
                     .insert(qname.clone(), ParsedModule { name: qname, ast });
@@ -148,7 +171,7 @@ impl<'a> ModulesParser<'a> {
 
         let mut parser = Parser::new(input, file.clone());
 
-        match parser.parse() {
+        match parser.check_balanced().and_then(|_| parser.parse()) {
             Ok(ast) => Some(ast),
             Err(err) => {
                 self.state.diagnostics.error(
@@ -169,7 +192,7 @@ mod tests {
     use super::*;
     use crate::config::Config;
     use std::env::temp_dir;
-    use std::fs::{remove_file, write};
+    use std::fs::{create_dir_all, remove_file, write};
 
     struct TempFile {
         path: PathBuf,
@@ -256,6 +279,31 @@ mod tests {
         assert_eq!(state.diagnostics.iter().count(), 1);
     }
 
+    #[test]
+    fn test_run_with_relative_imports() {
+        let file1 = TempFile::new("parsing1e");
+        let dir = temp_dir().join("parsing1e");
+        let file2 = TempFile { path: dir.join("parsing2e.inko") };
+
+        create_dir_all(&dir).unwrap();
+        write(file1.path(), "import self.parsing2e").unwrap();
+        write(file2.path(), "let A = 10").unwrap();
+
+        let mut state = State::new(Config::new());
+
+        state.config.add_source_directory(temp_dir());
+        state.config.implicit_imports = Vec::new();
+
+        let mut pass = ModulesParser::new(&mut state);
+        let mods = pass
+            .run(vec![(ModuleName::new("parsing1e"), file1.path().clone())]);
+        let names = mods.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+
+        assert_eq!(mods.len(), 2);
+        assert!(names.contains(&ModuleName::new("parsing1e.parsing2e")));
+        assert_eq!(state.diagnostics.iter().count(), 0);
+    }
+
     #[test]
     fn test_run_with_implicit_imports() {
         let file1 = TempFile::new("parsing1d");
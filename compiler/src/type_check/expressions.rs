@@ -1368,6 +1368,7 @@ impl<'a> CheckMethodBody<'a> {
             hir::Expression::Nil(ref mut n) => self.nil_literal(n),
             hir::Expression::Tuple(ref mut n) => self.tuple_literal(n, scope),
             hir::Expression::TypeCast(ref mut n) => self.type_cast(n, scope),
+            hir::Expression::Ascription(ref mut n) => self.ascription(n, scope),
             hir::Expression::Try(ref mut n) => self.try_expression(n, scope),
         }
     }
@@ -3953,6 +3954,31 @@ impl<'a> CheckMethodBody<'a> {
         node.resolved_type
     }
 
+    fn ascription(
+        &mut self,
+        node: &mut hir::Ascription,
+        scope: &mut LexicalScope,
+    ) -> TypeRef {
+        let value_type = self.expression(&mut node.value, scope);
+        let exp_type =
+            self.type_signature(&mut node.target_type, self.self_type);
+        let value_casted = value_type.cast_according_to(exp_type, self.db());
+
+        if !TypeChecker::check(self.db(), value_casted, exp_type) {
+            self.state.diagnostics.type_error(
+                format_type(self.db(), value_type),
+                format_type(self.db(), exp_type),
+                self.file(),
+                node.value.location().clone(),
+            );
+        }
+
+        // The ascription only checks the type, so the type of the expression
+        // (including its ownership) stays the same.
+        node.resolved_type = value_type;
+        node.resolved_type
+    }
+
     fn try_expression(
         &mut self,
         node: &mut hir::Try,
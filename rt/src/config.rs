@@ -18,6 +18,17 @@ macro_rules! set_from_env {
     }};
 }
 
+/// Sets a boolean configuration field based on an environment variable.
+///
+/// The values "1" and "true" enable the setting, any other value disables it.
+macro_rules! set_bool_from_env {
+    ($config:expr, $field:ident, $key:expr) => {{
+        if let Ok(raw_value) = var(concat!("INKO_", $key)) {
+            $config.$field = raw_value == "1" || raw_value == "true";
+        };
+    }};
+}
+
 /// The default number of network poller threads to use.
 ///
 /// We default to one thread because for most setups this is probably more than
@@ -38,6 +49,13 @@ pub struct Config {
     /// The number of process threads to run.
     pub process_threads: u16,
 
+    /// The maximum number of process threads to run.
+    ///
+    /// This value caps `process_threads`, which is useful when running in a
+    /// container with a CPU quota lower than the number of CPU cores on the
+    /// host. By default no limit is applied.
+    pub max_process_threads: u16,
+
     /// The number of backup process threads to spawn.
     pub backup_threads: u16,
 
@@ -51,6 +69,21 @@ pub struct Config {
     /// and use the value -1 to signal a file descriptor isn't registered with
     /// any poller.
     pub netpoll_threads: u8,
+
+    /// If newlines written to STDOUT and STDERR should be translated to CRLF
+    /// sequences.
+    ///
+    /// This is disabled by default, meaning output is written as-is.
+    pub translate_newlines: bool,
+
+    /// If the runtime should leave the handling of signals to the host
+    /// process, instead of changing the signal dispositions itself.
+    ///
+    /// This is meant for cases where the runtime is embedded in a host process
+    /// that handles signals itself. When enabled, the runtime doesn't ignore
+    /// SIGPIPE, meaning that writing to a closed pipe or socket terminates the
+    /// program, unless the host ignores or handles SIGPIPE itself.
+    pub disable_signal_handling: bool,
 }
 
 impl Config {
@@ -59,9 +92,12 @@ impl Config {
 
         Config {
             process_threads: cpu_count,
+            max_process_threads: u16::MAX,
             backup_threads: cpu_count * 4,
             netpoll_threads: DEFAULT_NETPOLL_THREADS,
             stack_size: DEFAULT_STACK_SIZE,
+            translate_newlines: false,
+            disable_signal_handling: false,
         }
     }
 
@@ -69,15 +105,31 @@ impl Config {
         let mut config = Config::new();
 
         set_from_env!(config, process_threads, "PROCESS_THREADS", u16);
+        set_from_env!(config, max_process_threads, "MAX_PROCESS_THREADS", u16);
         set_from_env!(config, backup_threads, "BACKUP_THREADS", u16);
         set_from_env!(config, netpoll_threads, "NETPOLL_THREADS", u8);
         set_from_env!(config, stack_size, "STACK_SIZE", u32);
 
+        set_bool_from_env!(config, translate_newlines, "TRANSLATE_NEWLINES");
+        set_bool_from_env!(
+            config,
+            disable_signal_handling,
+            "DISABLE_SIGNAL_HANDLING"
+        );
+
         config.verify();
         config
     }
 
     fn verify(&mut self) {
+        if self.max_process_threads == 0 {
+            self.max_process_threads = 1;
+        }
+
+        if self.process_threads > self.max_process_threads {
+            self.process_threads = self.max_process_threads;
+        }
+
         if self.netpoll_threads > MAX_NETPOLL_THREADS {
             self.netpoll_threads = MAX_NETPOLL_THREADS;
         }
@@ -92,6 +144,7 @@ mod tests {
         match key {
             "INKO_FOO" => Ok("1"),
             "INKO_BAR" => Ok("0"),
+            "INKO_TRUE" => Ok("true"),
             "INKO_NETPOLL_THREADS" => Ok("4"),
             _ => Err(()),
         }
@@ -113,6 +166,28 @@ mod tests {
         assert_eq!(cfg.process_threads, 1);
     }
 
+    #[test]
+    fn test_set_bool_from_env() {
+        let mut cfg = Config::new();
+
+        set_bool_from_env!(cfg, disable_signal_handling, "FOO");
+        assert!(cfg.disable_signal_handling);
+
+        set_bool_from_env!(cfg, disable_signal_handling, "BAR");
+        assert!(!cfg.disable_signal_handling);
+
+        set_bool_from_env!(cfg, translate_newlines, "TRUE");
+        assert!(cfg.translate_newlines);
+
+        set_bool_from_env!(cfg, translate_newlines, "BAZ");
+        assert!(cfg.translate_newlines);
+    }
+
+    #[test]
+    fn test_new_translate_newlines() {
+        assert!(!Config::new().translate_newlines);
+    }
+
     #[test]
     fn test_verify() {
         let mut cfg = Config::new();
@@ -125,4 +200,22 @@ mod tests {
         cfg.verify();
         assert_eq!(cfg.netpoll_threads, MAX_NETPOLL_THREADS);
     }
+
+    #[test]
+    fn test_verify_max_process_threads() {
+        let mut cfg = Config::new();
+
+        cfg.process_threads = 8;
+        cfg.verify();
+        assert_eq!(cfg.process_threads, 8);
+
+        cfg.max_process_threads = 4;
+        cfg.verify();
+        assert_eq!(cfg.process_threads, 4);
+
+        cfg.max_process_threads = 0;
+        cfg.verify();
+        assert_eq!(cfg.max_process_threads, 1);
+        assert_eq!(cfg.process_threads, 1);
+    }
 }
@@ -19,6 +19,7 @@ pub mod scheduler;
 pub mod socket;
 pub mod stack;
 pub mod state;
+pub mod watcher;
 
 #[cfg(test)]
 pub mod test;
@@ -1,9 +1,12 @@
 use crate::page::{multiple_of_page_size, page_size};
+use rustix::io::Errno;
 use rustix::mm::{
-    mmap_anonymous, mprotect, munmap, MapFlags, MprotectFlags, ProtFlags,
+    mmap, mmap_anonymous, mprotect, munmap, MapFlags, MprotectFlags, ProtFlags,
 };
+use std::fs::File;
 use std::io::{Error, Result as IoResult};
 use std::ptr::null_mut;
+use std::slice;
 
 /// A chunk of memory created using `mmap` and similar functions.
 pub(crate) struct MemoryMap {
@@ -69,9 +72,80 @@ impl Drop for MemoryMap {
     }
 }
 
+/// A read-only memory mapping of a region of a file.
+///
+/// The mapping only allows reads, so it's not possible to write to the mapped
+/// memory.
+pub(crate) struct MappedFile {
+    /// The start of the mapping.
+    ///
+    /// Because mappings must start at an offset that is a multiple of the page
+    /// size, this may point to data before the requested region.
+    ptr: *mut u8,
+
+    /// The size of the entire mapping.
+    len: usize,
+
+    /// The number of bytes between `ptr` and the start of the requested region.
+    start: usize,
+}
+
+impl MappedFile {
+    pub(crate) fn new(
+        file: &File,
+        offset: u64,
+        size: usize,
+    ) -> IoResult<MappedFile> {
+        let file_size = file.metadata()?.len();
+
+        // Reading pages beyond the end of the file results in a SIGBUS, so we
+        // require the region to reside within the file.
+        if size == 0 || offset.saturating_add(size as u64) > file_size {
+            return Err(Error::from_raw_os_error(Errno::INVAL.raw_os_error()));
+        }
+
+        let start = (offset % page_size() as u64) as usize;
+        let len = size + start;
+        let res = unsafe {
+            mmap(
+                null_mut(),
+                len,
+                ProtFlags::READ,
+                MapFlags::PRIVATE,
+                file,
+                offset - start as u64,
+            )
+        };
+
+        match res {
+            Ok(ptr) => Ok(MappedFile { ptr: ptr as *mut u8, len, start }),
+            Err(e) => Err(Error::from_raw_os_error(e.raw_os_error())),
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        unsafe {
+            slice::from_raw_parts(
+                self.ptr.add(self.start),
+                self.len - self.start,
+            )
+        }
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = munmap(self.ptr as _, self.len);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env::temp_dir;
+    use std::fs::{remove_file, write};
 
     #[test]
     fn test_new() {
@@ -88,4 +162,26 @@ mod tests {
 
         assert!(map.protect(0).is_ok());
     }
+
+    #[test]
+    fn test_mapped_file() {
+        let path = temp_dir().join("inko_test_mapped_file.txt");
+        let data = vec![42_u8; page_size() + 3];
+
+        write(&path, &data).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let map1 = MappedFile::new(&file, 0, data.len()).unwrap();
+        let map2 = MappedFile::new(&file, 2, 4).unwrap();
+        let map3 = MappedFile::new(&file, page_size() as u64 + 1, 2).unwrap();
+
+        assert_eq!(map1.as_slice(), &data[..]);
+        assert_eq!(map2.as_slice(), &[42, 42, 42, 42]);
+        assert_eq!(map3.as_slice(), &[42, 42]);
+        assert!(MappedFile::new(&file, 0, 0).is_err());
+        assert!(MappedFile::new(&file, 0, data.len() + 1).is_err());
+        assert!(MappedFile::new(&file, data.len() as u64, 1).is_err());
+
+        remove_file(&path).unwrap();
+    }
 }
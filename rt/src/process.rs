@@ -5,13 +5,15 @@ use crate::scheduler::timeouts::Timeout;
 use crate::stack::Stack;
 use backtrace;
 use std::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use std::any::Any;
 use std::cell::UnsafeCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::mem::{align_of, forget, size_of, ManuallyDrop};
 use std::ops::Drop;
 use std::ops::{Deref, DerefMut};
 use std::ptr::{drop_in_place, null_mut, NonNull};
 use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Mutex, MutexGuard};
 
 const INKO_SYMBOL_IDENTIFIER: &str = "_IM_";
@@ -278,6 +280,60 @@ impl RescheduleRights {
     }
 }
 
+/// A key used for storing values in the native storage of a process.
+///
+/// Each native extension should register its own key once (e.g. using a lazily
+/// initialized static), then use it for all processes.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NativeStorageKey(usize);
+
+impl NativeStorageKey {
+    /// Registers and returns a new unique key.
+    pub fn register() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        Self(NEXT.fetch_add(1, Ordering::AcqRel))
+    }
+}
+
+/// Process-local storage for values managed by native code.
+///
+/// This allows native functions to associate state with a process (e.g. a
+/// buffer), without the need for adding fields to `Process`. Values are
+/// dropped when the process is dropped.
+#[derive(Default)]
+pub struct NativeStorage {
+    values: HashMap<NativeStorageKey, Box<dyn Any + Send>>,
+}
+
+impl NativeStorage {
+    /// Returns a reference to the value of the given key.
+    ///
+    /// If the key isn't set, or the value isn't of type `T`, a `None` is
+    /// returned.
+    pub fn get<T: Any>(&self, key: NativeStorageKey) -> Option<&T> {
+        self.values.get(&key).and_then(|v| v.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the value of the given key.
+    pub fn get_mut<T: Any>(&mut self, key: NativeStorageKey) -> Option<&mut T> {
+        self.values.get_mut(&key).and_then(|v| v.downcast_mut())
+    }
+
+    /// Stores a value, overwriting any existing value of the same key.
+    pub fn insert<T: Any + Send>(&mut self, key: NativeStorageKey, value: T) {
+        self.values.insert(key, Box::new(value));
+    }
+
+    /// Removes the value of the given key, returning it if it's of type `T`.
+    pub fn remove<T: Any>(&mut self, key: NativeStorageKey) -> Option<T> {
+        self.values
+            .remove(&key)
+            .and_then(|v| v.downcast().ok())
+            .map(|v: Box<T>| *v)
+    }
+}
+
 /// The shared state of a process.
 ///
 /// This state is shared by both the process and its clients.
@@ -293,6 +349,11 @@ pub(crate) struct ProcessState {
     /// If missing and the process is suspended, it means the process is
     /// suspended indefinitely.
     timeout: Option<ArcWithoutWeak<Timeout>>,
+
+    /// The values stored by native code for this process.
+    ///
+    /// This storage is allocated on demand, as most processes won't use it.
+    native_storage: Option<Box<NativeStorage>>,
 }
 
 impl ProcessState {
@@ -301,9 +362,14 @@ impl ProcessState {
             mailbox: Mailbox::new(),
             status: ProcessStatus::new(),
             timeout: None,
+            native_storage: None,
         }
     }
 
+    pub(crate) fn native_storage(&mut self) -> &mut NativeStorage {
+        self.native_storage.get_or_insert_with(Default::default)
+    }
+
     pub(crate) fn has_same_timeout(
         &self,
         timeout: &ArcWithoutWeak<Timeout>,
@@ -603,6 +669,18 @@ impl Process {
         self.state.lock().unwrap()
     }
 
+    /// Calls the closure with the native storage of this process.
+    ///
+    /// The shared state of the process is locked while the closure runs, so
+    /// the closure shouldn't perform any operations that require access to
+    /// this state (e.g. sending messages to the process).
+    pub fn native_storage<R>(
+        &self,
+        func: impl FnOnce(&mut NativeStorage) -> R,
+    ) -> R {
+        func(self.state().native_storage())
+    }
+
     /// Acquires the run lock of this process.
     ///
     /// We use an explicit lifetime here so the mutex guard's lifetime isn't
@@ -953,15 +1031,15 @@ mod tests {
 
         if cfg!(any(target_os = "linux", target_os = "freebsd")) {
             assert_eq!(size_of::<UnsafeCell<Mutex<()>>>(), 8);
-            assert_eq!(size_of::<Process>(), 112);
+            assert_eq!(size_of::<Process>(), 120);
             assert_eq!(size_of::<Channel>(), 96);
         } else {
             assert_eq!(size_of::<UnsafeCell<Mutex<()>>>(), 16);
-            assert_eq!(size_of::<Process>(), 128);
+            assert_eq!(size_of::<Process>(), 136);
             assert_eq!(size_of::<Channel>(), 104);
         }
 
-        assert_eq!(size_of::<ProcessState>(), 48);
+        assert_eq!(size_of::<ProcessState>(), 56);
         assert_eq!(size_of::<Option<NonNull<Thread>>>(), 8);
         assert_eq!(size_of::<ChannelState>(), 88);
     }
@@ -976,9 +1054,9 @@ mod tests {
         assert_eq!(
             offset_of!(proc, fields),
             if cfg!(any(target_os = "linux", target_os = "freebsd")) {
-                112
+                120
             } else {
-                128
+                136
             }
         );
     }
@@ -1226,6 +1304,46 @@ mod tests {
         assert!(process.state().status.is_waiting());
     }
 
+    #[test]
+    fn test_native_storage_key_register() {
+        assert_ne!(NativeStorageKey::register(), NativeStorageKey::register());
+    }
+
+    #[test]
+    fn test_native_storage() {
+        let key1 = NativeStorageKey::register();
+        let key2 = NativeStorageKey::register();
+        let mut storage = NativeStorage::default();
+
+        storage.insert(key1, 42_u64);
+
+        assert_eq!(storage.get::<u64>(key1), Some(&42));
+        assert_eq!(storage.get::<u32>(key1), None);
+        assert_eq!(storage.get::<u64>(key2), None);
+
+        *storage.get_mut::<u64>(key1).unwrap() = 50;
+
+        assert_eq!(storage.remove::<u64>(key1), Some(50));
+        assert_eq!(storage.get::<u64>(key1), None);
+    }
+
+    #[test]
+    fn test_process_native_storage() {
+        let class = empty_process_class("A");
+        let stack = Stack::new(32);
+        let process = OwnedProcess::new(Process::alloc(*class, stack));
+        let key = NativeStorageKey::register();
+
+        assert!(process.state().native_storage.is_none());
+
+        process.native_storage(|s| s.insert(key, vec![1_u8, 2]));
+
+        assert_eq!(
+            process.native_storage(|s| s.get::<Vec<u8>>(key).cloned()),
+            Some(vec![1, 2])
+        );
+    }
+
     #[test]
     fn test_process_timeout_expired() {
         let state = setup();
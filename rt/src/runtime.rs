@@ -1,5 +1,7 @@
 mod byte_array;
 mod class;
+mod compression;
+mod encoding;
 mod env;
 mod float;
 mod fs;
@@ -13,12 +15,13 @@ mod stdio;
 mod string;
 mod sys;
 mod time;
+mod watcher;
 
 use crate::config::Config;
 use crate::mem::ClassPointer;
 use crate::network_poller::Worker as NetworkPollerWorker;
 use crate::process::{NativeAsyncMethod, Process};
-use crate::scheduler::{number_of_cores, pin_thread_to_core};
+use crate::scheduler::pin_thread_to_core;
 use crate::stack::Stack;
 use crate::state::{MethodCounts, RcState, State};
 use std::ffi::CStr;
@@ -77,7 +80,10 @@ pub unsafe extern "system" fn inko_runtime_start(
     class: ClassPointer,
     method: NativeAsyncMethod,
 ) {
-    signal(SIGPIPE, SIG_IGN);
+    if !(*runtime).state.config.disable_signal_handling {
+        signal(SIGPIPE, SIG_IGN);
+    }
+
     (*runtime).start(class, method);
     flush_stdout();
 }
@@ -123,7 +129,7 @@ impl Runtime {
     /// waits/blocks until completion).
     fn start(&self, main_class: ClassPointer, main_method: NativeAsyncMethod) {
         let state = self.state.clone();
-        let cores = number_of_cores();
+        let cores = self.state.cores;
 
         thread::Builder::new()
             .name("timeout".to_string())
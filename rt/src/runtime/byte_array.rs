@@ -2,6 +2,7 @@ use crate::mem::{ByteArray, String as InkoString};
 use crate::state::State;
 use std::cmp::min;
 use std::slice;
+use std::str;
 
 #[no_mangle]
 pub unsafe extern "system" fn inko_byte_array_new(
@@ -70,6 +71,13 @@ pub unsafe extern "system" fn inko_byte_array_eq(
     ((*lhs).value == (*rhs).value) as i64
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_byte_array_is_utf8(
+    bytes: *const ByteArray,
+) -> i64 {
+    str::from_utf8(&(*bytes).value).is_ok() as i64
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_byte_array_clear(bytes: *mut ByteArray) {
     (*bytes).value.clear();
@@ -154,6 +162,15 @@ pub unsafe extern "system" fn inko_byte_array_resize(
     (*bytes).value.resize(size as usize, filler as u8);
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_byte_array_fill(
+    bytes: *mut ByteArray,
+    size: i64,
+    value: i64,
+) {
+    (*bytes).value.get_unchecked_mut(..size as usize).fill(value as u8);
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_byte_array_to_pointer(
     bytes: *mut ByteArray,
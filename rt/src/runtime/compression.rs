@@ -0,0 +1,455 @@
+use crate::mem::ByteArray;
+use crate::result::Result as InkoResult;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::{Compression, Crc, Decompress, FlushDecompress, Status};
+use std::io::Write;
+use std::slice;
+
+/// The format for raw deflate data, as defined in RFC 1951.
+const DEFLATE: i64 = 0;
+
+/// The format for gzip data, as defined in RFC 1952.
+const GZIP: i64 = 1;
+
+/// The error produced when the input isn't valid compressed data.
+const INVALID_DATA: i64 = 0;
+
+/// The error produced when the input ends before the end of the compressed
+/// data.
+const UNEXPECTED_END: i64 = 1;
+
+/// The amount of output space to reserve when decompressing data.
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// The bytes every gzip stream starts with.
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// The compression method used by gzip streams, which is always deflate.
+const GZIP_DEFLATE: u8 = 8;
+
+/// The size of the fixed part of a gzip header.
+const GZIP_HEADER_SIZE: usize = 10;
+
+/// The size of a gzip trailer, containing the CRC32 and size of the
+/// decompressed data.
+const GZIP_TRAILER_SIZE: usize = 8;
+
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+
+/// Returns the size of the gzip header at the start of `input`.
+///
+/// If the input doesn't contain the full header yet, a `None` is returned.
+fn gzip_header_size(input: &[u8]) -> Result<Option<usize>, i64> {
+    if input.len() < GZIP_HEADER_SIZE {
+        return Ok(None);
+    }
+
+    if input[0..2] != GZIP_MAGIC || input[2] != GZIP_DEFLATE {
+        return Err(INVALID_DATA);
+    }
+
+    let flags = input[3];
+    let mut size = GZIP_HEADER_SIZE;
+
+    if flags & GZIP_FEXTRA != 0 {
+        let Some(len) = input.get(size..size + 2) else { return Ok(None) };
+
+        size += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+
+    for flag in [GZIP_FNAME, GZIP_FCOMMENT] {
+        if flags & flag != 0 {
+            let Some(rest) = input.get(size..) else { return Ok(None) };
+            let Some(end) = rest.iter().position(|&b| b == 0) else {
+                return Ok(None);
+            };
+
+            size += end + 1;
+        }
+    }
+
+    if flags & GZIP_FHCRC != 0 {
+        size += 2;
+    }
+
+    Ok(if size <= input.len() { Some(size) } else { None })
+}
+
+/// A type that compresses data.
+pub(crate) enum Encoder {
+    Deflate(DeflateEncoder<Vec<u8>>),
+    Gzip(GzEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(format: i64, level: i64) -> Encoder {
+        let level = Compression::new(level.clamp(0, 9) as u32);
+
+        if format == DEFLATE {
+            Encoder::Deflate(DeflateEncoder::new(Vec::new(), level))
+        } else {
+            Encoder::Gzip(GzEncoder::new(Vec::new(), level))
+        }
+    }
+
+    fn write(&mut self, input: &[u8]) {
+        // Writing to a Vec never fails, and neither does compressing data.
+        match self {
+            Encoder::Deflate(enc) => enc.write_all(input),
+            Encoder::Gzip(enc) => enc.write_all(input),
+        }
+        .expect("compressing data into a Vec can't fail");
+    }
+
+    fn finish(&mut self) {
+        match self {
+            Encoder::Deflate(enc) => enc.try_finish(),
+            Encoder::Gzip(enc) => enc.try_finish(),
+        }
+        .expect("compressing data into a Vec can't fail");
+    }
+
+    fn output(&mut self) -> &mut Vec<u8> {
+        match self {
+            Encoder::Deflate(enc) => enc.get_mut(),
+            Encoder::Gzip(enc) => enc.get_mut(),
+        }
+    }
+}
+
+/// A type that decompresses data.
+///
+/// The data is decompressed as it's written, allowing data to be decompressed
+/// in chunks. For gzip data, the header and trailer are handled here, such that
+/// a truncated stream produces an error instead of being silently accepted.
+pub(crate) struct Decoder {
+    /// A boolean indicating if the data uses the gzip format.
+    gzip: bool,
+
+    /// The raw deflate decompressor.
+    inflate: Decompress,
+
+    /// The bytes of a gzip header that is yet to be parsed completely.
+    header: Vec<u8>,
+
+    /// A boolean indicating if the gzip header is parsed.
+    header_done: bool,
+
+    /// The bytes of the gzip trailer received so far.
+    trailer: Vec<u8>,
+
+    /// The checksum of the decompressed data, used for gzip data.
+    crc: Crc,
+
+    /// The decompressed data that is yet to be read.
+    output: Vec<u8>,
+
+    /// A boolean indicating if the end of the deflate data is reached.
+    done: bool,
+}
+
+impl Decoder {
+    fn new(format: i64) -> Decoder {
+        let gzip = format == GZIP;
+
+        Decoder {
+            gzip,
+            inflate: Decompress::new(false),
+            header: Vec::new(),
+            header_done: !gzip,
+            trailer: Vec::new(),
+            crc: Crc::new(),
+            output: Vec::new(),
+            done: false,
+        }
+    }
+
+    fn write(&mut self, input: &[u8]) -> Result<(), i64> {
+        if self.header_done {
+            return self.write_body(input, FlushDecompress::None);
+        }
+
+        self.header.extend_from_slice(input);
+
+        let Some(size) = gzip_header_size(&self.header)? else {
+            return Ok(());
+        };
+        let rest = self.header.split_off(size);
+
+        self.header = Vec::new();
+        self.header_done = true;
+        self.write_body(&rest, FlushDecompress::None)
+    }
+
+    fn finish(&mut self) -> Result<(), i64> {
+        if !self.header_done {
+            return Err(UNEXPECTED_END);
+        }
+
+        self.write_body(&[], FlushDecompress::Finish)?;
+
+        if !self.done {
+            return Err(UNEXPECTED_END);
+        }
+
+        if !self.gzip {
+            return Ok(());
+        }
+
+        if self.trailer.len() < GZIP_TRAILER_SIZE {
+            return Err(UNEXPECTED_END);
+        }
+
+        let crc = u32::from_le_bytes(self.trailer[0..4].try_into().unwrap());
+        let size = u32::from_le_bytes(self.trailer[4..8].try_into().unwrap());
+
+        if crc != self.crc.sum() || size != self.crc.amount() {
+            return Err(INVALID_DATA);
+        }
+
+        Ok(())
+    }
+
+    fn write_body(
+        &mut self,
+        mut input: &[u8],
+        flush: FlushDecompress,
+    ) -> Result<(), i64> {
+        let start = self.output.len();
+
+        while !self.done {
+            self.output.reserve(CHUNK_SIZE);
+
+            let before_in = self.inflate.total_in();
+            let before_out = self.inflate.total_out();
+            let status = self
+                .inflate
+                .decompress_vec(input, &mut self.output, flush)
+                .map_err(|_| INVALID_DATA)?;
+            let read = (self.inflate.total_in() - before_in) as usize;
+            let written = self.inflate.total_out() - before_out;
+
+            input = &input[read..];
+
+            match status {
+                Status::StreamEnd => self.done = true,
+                _ if read == 0 && written == 0 => break,
+                _ => {}
+            }
+        }
+
+        if self.gzip {
+            self.crc.update(&self.output[start..]);
+
+            // Any data that comes after the trailer is ignored.
+            if self.done {
+                let size =
+                    (GZIP_TRAILER_SIZE - self.trailer.len()).min(input.len());
+
+                self.trailer.extend_from_slice(&input[0..size]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+unsafe fn move_output(output: &mut Vec<u8>, buffer: *mut ByteArray) -> i64 {
+    let size = output.len() as i64;
+
+    (*buffer).value.append(output);
+    size
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_compressor_new(
+    format: i64,
+    level: i64,
+) -> *mut Encoder {
+    Box::into_raw(Box::new(Encoder::new(format, level)))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_compressor_write(
+    encoder: *mut Encoder,
+    bytes: *const u8,
+    size: i64,
+) {
+    (*encoder).write(slice::from_raw_parts(bytes, size as _));
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_compressor_read(
+    encoder: *mut Encoder,
+    buffer: *mut ByteArray,
+) -> i64 {
+    move_output((*encoder).output(), buffer)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_compressor_finish(
+    encoder: *mut Encoder,
+    buffer: *mut ByteArray,
+) -> i64 {
+    (*encoder).finish();
+    move_output((*encoder).output(), buffer)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_compressor_drop(encoder: *mut Encoder) {
+    drop(Box::from_raw(encoder));
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_decompressor_new(
+    format: i64,
+) -> *mut Decoder {
+    Box::into_raw(Box::new(Decoder::new(format)))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_decompressor_write(
+    decoder: *mut Decoder,
+    bytes: *const u8,
+    size: i64,
+) -> InkoResult {
+    match (*decoder).write(slice::from_raw_parts(bytes, size as _)) {
+        Ok(_) => InkoResult::ok(0 as _),
+        Err(code) => InkoResult::error(code as _),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_decompressor_read(
+    decoder: *mut Decoder,
+    buffer: *mut ByteArray,
+) -> i64 {
+    move_output(&mut (*decoder).output, buffer)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_decompressor_finish(
+    decoder: *mut Decoder,
+    buffer: *mut ByteArray,
+) -> InkoResult {
+    match (*decoder).finish() {
+        Ok(_) => {
+            InkoResult::ok(move_output(&mut (*decoder).output, buffer) as _)
+        }
+        Err(code) => InkoResult::error(code as _),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_decompressor_drop(decoder: *mut Decoder) {
+    drop(Box::from_raw(decoder));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compress(format: i64, input: &[u8]) -> Vec<u8> {
+        let mut encoder = Encoder::new(format, 6);
+
+        encoder.write(input);
+        encoder.finish();
+        encoder.output().split_off(0)
+    }
+
+    fn decompress(
+        format: i64,
+        input: &[u8],
+        chunk: usize,
+    ) -> Result<Vec<u8>, i64> {
+        let mut decoder = Decoder::new(format);
+
+        for bytes in input.chunks(chunk) {
+            decoder.write(bytes)?;
+        }
+
+        decoder.finish()?;
+        Ok(decoder.output)
+    }
+
+    #[test]
+    fn test_gzip_header_size() {
+        let mut header = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 3];
+
+        assert_eq!(gzip_header_size(&header[0..4]), Ok(None));
+        assert_eq!(gzip_header_size(&header), Ok(Some(10)));
+
+        header[3] = GZIP_FNAME | GZIP_FHCRC;
+        header.extend_from_slice(b"foo");
+
+        assert_eq!(gzip_header_size(&header), Ok(None));
+
+        header.extend_from_slice(&[0, 1, 2]);
+
+        assert_eq!(gzip_header_size(&header), Ok(Some(16)));
+
+        header[0] = 0;
+
+        assert_eq!(gzip_header_size(&header), Err(INVALID_DATA));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = b"hello world ".repeat(10_000);
+
+        for format in [DEFLATE, GZIP] {
+            let compressed = compress(format, &input);
+
+            assert!(compressed.len() < input.len());
+
+            for chunk in [1, 7, 1024, compressed.len()] {
+                assert_eq!(
+                    decompress(format, &compressed, chunk).as_ref(),
+                    Ok(&input)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompress_empty() {
+        for format in [DEFLATE, GZIP] {
+            let compressed = compress(format, b"");
+
+            assert_eq!(decompress(format, &compressed, 4), Ok(Vec::new()));
+            assert_eq!(decompress(format, &[], 4), Err(UNEXPECTED_END));
+        }
+    }
+
+    #[test]
+    fn test_decompress_truncated() {
+        for format in [DEFLATE, GZIP] {
+            let compressed = compress(format, b"hello world");
+
+            for size in 1..compressed.len() {
+                assert_eq!(
+                    decompress(format, &compressed[0..size], 4),
+                    Err(UNEXPECTED_END),
+                    "format {} with {} bytes",
+                    format,
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompress_invalid() {
+        let mut compressed = compress(GZIP, b"hello world");
+        let last = compressed.len() - 1;
+
+        compressed[last] ^= 0xFF;
+
+        assert_eq!(decompress(GZIP, &compressed, 4), Err(INVALID_DATA));
+        assert_eq!(decompress(GZIP, &[0; 20], 4), Err(INVALID_DATA));
+        assert_eq!(decompress(DEFLATE, &[0xFF; 20], 4), Err(INVALID_DATA));
+    }
+}
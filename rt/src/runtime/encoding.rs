@@ -0,0 +1,229 @@
+use crate::mem::{ByteArray, String as InkoString};
+use crate::result::Result as InkoResult;
+use crate::state::State;
+use std::slice;
+
+/// The error produced when the size of the input is invalid.
+///
+/// Other decoding errors use the (positive) index of the invalid byte.
+const INVALID_SIZE: i64 = -1;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const BASE64_PADDING: u8 = b'=';
+
+const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+fn base64_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity((input.len() + 2) / 3 * 4);
+
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as usize;
+        let b1 = chunk.get(1).cloned().unwrap_or(0) as usize;
+        let b2 = chunk.get(2).cloned().unwrap_or(0) as usize;
+
+        output.push(BASE64_ALPHABET[b0 >> 2] as char);
+        output.push(BASE64_ALPHABET[((b0 & 0x3) << 4) | (b1 >> 4)] as char);
+
+        if chunk.len() > 1 {
+            output.push(BASE64_ALPHABET[((b1 & 0xF) << 2) | (b2 >> 6)] as char);
+        } else {
+            output.push(BASE64_PADDING as char);
+        }
+
+        if chunk.len() > 2 {
+            output.push(BASE64_ALPHABET[b2 & 0x3F] as char);
+        } else {
+            output.push(BASE64_PADDING as char);
+        }
+    }
+
+    output
+}
+
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+fn base64_decode(input: &[u8]) -> Result<Vec<u8>, i64> {
+    if input.len() % 4 != 0 {
+        return Err(INVALID_SIZE);
+    }
+
+    // Padding is only allowed at the end, and only up to two bytes.
+    let padding =
+        input.iter().rev().take_while(|&&b| b == BASE64_PADDING).count();
+
+    if padding > 2 {
+        return Err((input.len() - padding) as i64);
+    }
+
+    let data = &input[0..input.len() - padding];
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+
+    for (index, &byte) in data.iter().enumerate() {
+        let Some(value) = base64_value(byte) else {
+            return Err(index as i64);
+        };
+
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Ok(output)
+}
+
+fn hex_encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len() * 2);
+
+    for &byte in input {
+        output.push(HEX_ALPHABET[(byte >> 4) as usize] as char);
+        output.push(HEX_ALPHABET[(byte & 0xF) as usize] as char);
+    }
+
+    output
+}
+
+fn hex_decode(input: &[u8]) -> Result<Vec<u8>, i64> {
+    if input.len() % 2 != 0 {
+        return Err(INVALID_SIZE);
+    }
+
+    let mut output = Vec::with_capacity(input.len() / 2);
+
+    for (index, pair) in input.chunks(2).enumerate() {
+        let mut byte = 0;
+
+        for (offset, &digit) in pair.iter().enumerate() {
+            let Some(value) = (digit as char).to_digit(16) else {
+                return Err((index * 2 + offset) as i64);
+            };
+
+            byte = (byte << 4) | value as u8;
+        }
+
+        output.push(byte);
+    }
+
+    Ok(output)
+}
+
+unsafe fn decode_result(
+    state: *const State,
+    result: Result<Vec<u8>, i64>,
+) -> InkoResult {
+    match result {
+        Ok(bytes) => InkoResult::ok(ByteArray::alloc(
+            (*state).byte_array_class,
+            bytes,
+        ) as _),
+        Err(code) => InkoResult::error(code as _),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_base64_encode(
+    state: *const State,
+    bytes: *const u8,
+    size: i64,
+) -> *const InkoString {
+    let input = slice::from_raw_parts(bytes, size as _);
+
+    InkoString::alloc((*state).string_class, base64_encode(input))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_base64_decode(
+    state: *const State,
+    bytes: *const u8,
+    size: i64,
+) -> InkoResult {
+    let input = slice::from_raw_parts(bytes, size as _);
+
+    decode_result(state, base64_decode(input))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_hex_encode(
+    state: *const State,
+    bytes: *const u8,
+    size: i64,
+) -> *const InkoString {
+    let input = slice::from_raw_parts(bytes, size as _);
+
+    InkoString::alloc((*state).string_class, hex_encode(input))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_hex_decode(
+    state: *const State,
+    bytes: *const u8,
+    size: i64,
+) -> InkoResult {
+    let input = slice::from_raw_parts(bytes, size as _);
+
+    decode_result(state, hex_decode(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg==");
+        assert_eq!(base64_encode(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode(&[255, 254, 253]), "//79");
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(base64_decode(b""), Ok(Vec::new()));
+        assert_eq!(base64_decode(b"Zg=="), Ok(b"f".to_vec()));
+        assert_eq!(base64_decode(b"Zm8="), Ok(b"fo".to_vec()));
+        assert_eq!(base64_decode(b"Zm9v"), Ok(b"foo".to_vec()));
+        assert_eq!(base64_decode(b"Zm9vYmFy"), Ok(b"foobar".to_vec()));
+        assert_eq!(base64_decode(b"//79"), Ok(vec![255, 254, 253]));
+        assert_eq!(base64_decode(b"Zm9"), Err(INVALID_SIZE));
+        assert_eq!(base64_decode(b"Zm9!"), Err(3));
+        assert_eq!(base64_decode(b"Z==="), Err(1));
+        assert_eq!(base64_decode(b"Zg=v"), Err(2));
+    }
+
+    #[test]
+    fn test_hex_encode() {
+        assert_eq!(hex_encode(b""), "");
+        assert_eq!(hex_encode(&[0, 15, 16, 255]), "000f10ff");
+    }
+
+    #[test]
+    fn test_hex_decode() {
+        assert_eq!(hex_decode(b""), Ok(Vec::new()));
+        assert_eq!(hex_decode(b"000f10ff"), Ok(vec![0, 15, 16, 255]));
+        assert_eq!(hex_decode(b"000F10FF"), Ok(vec![0, 15, 16, 255]));
+        assert_eq!(hex_decode(b"0"), Err(INVALID_SIZE));
+        assert_eq!(hex_decode(b"0g"), Err(1));
+        assert_eq!(hex_decode(b"00g0"), Err(2));
+    }
+}
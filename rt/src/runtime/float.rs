@@ -1,20 +1,77 @@
 use crate::mem::String as InkoString;
 use crate::state::State;
 
+/// Returns the text to use for the special float values (NaN and infinity),
+/// if `value` is such a value.
+fn special_value(value: f64) -> Option<&'static str> {
+    if value.is_infinite() && value.is_sign_positive() {
+        Some("Infinity")
+    } else if value.is_infinite() {
+        Some("-Infinity")
+    } else if value.is_nan() {
+        Some("NaN")
+    } else {
+        None
+    }
+}
+
+/// Formats a float using the given precision and notation.
+///
+/// A negative precision results in as many fractional digits as needed to
+/// represent the value exactly. Values exactly halfway between two candidates
+/// are rounded to the nearest even digit.
+fn format(value: f64, precision: i64, scientific: bool) -> String {
+    if let Some(val) = special_value(value) {
+        return val.to_string();
+    }
+
+    match (precision, scientific) {
+        (p, true) if p >= 0 => format!("{:.*e}", p as usize, value),
+        (_, true) => format!("{:e}", value),
+        (p, false) if p >= 0 => format!("{:.*}", p as usize, value),
+        (_, false) => format!("{:?}", value),
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_float_to_string(
     state: *const State,
     value: f64,
 ) -> *const InkoString {
-    let string = if value.is_infinite() && value.is_sign_positive() {
-        "Infinity".to_string()
-    } else if value.is_infinite() {
-        "-Infinity".to_string()
-    } else if value.is_nan() {
-        "NaN".to_string()
-    } else {
-        format!("{:?}", value)
-    };
+    InkoString::alloc((*state).string_class, format(value, -1, false))
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_float_format(
+    state: *const State,
+    value: f64,
+    precision: i64,
+    scientific: i64,
+) -> *const InkoString {
+    let string = format(value, precision, scientific != 0);
 
     InkoString::alloc((*state).string_class, string)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(1.5, -1, false), "1.5");
+        assert_eq!(format(1.0, -1, false), "1.0");
+        assert_eq!(format(1.25, 1, false), "1.2");
+        assert_eq!(format(1.35, 1, false), "1.4");
+        assert_eq!(format(2.5, 0, false), "2");
+        assert_eq!(format(3.5, 0, false), "4");
+        assert_eq!(format(1.5, 3, false), "1.500");
+        assert_eq!(format(-0.0, 2, false), "-0.00");
+        assert_eq!(format(1234.5, -1, true), "1.2345e3");
+        assert_eq!(format(1234.5, 2, true), "1.23e3");
+        assert_eq!(format(0.00015, 1, true), "1.5e-4");
+        assert_eq!(format(f64::NAN, 2, false), "NaN");
+        assert_eq!(format(f64::INFINITY, 2, true), "Infinity");
+        assert_eq!(format(f64::NEG_INFINITY, -1, false), "-Infinity");
+    }
+}
@@ -1,13 +1,61 @@
 use crate::mem::{ByteArray, String as InkoString};
+use crate::memory_map::MappedFile;
 use crate::process::ProcessPointer;
 use crate::result::Result as InkoResult;
-use crate::runtime::helpers::read_into;
+use crate::runtime::helpers::{
+    io_slices, is_blocking, read_into, retry_interrupted, set_blocking,
+    write_all,
+};
 use crate::state::State;
+use rustix::io::Errno;
+use std::cmp::min;
+use std::env;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Seek, SeekFrom, Write};
-use std::path::PathBuf;
+use std::os::unix::fs::{symlink, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+#[cfg(not(target_os = "macos"))]
+use rustix::pipe::{pipe_with, PipeFlags};
+
+#[cfg(target_os = "macos")]
+use rustix::{
+    io::{fcntl_setfd, FdFlags},
+    pipe::pipe,
+};
+
+/// The values used for the types of files, in the same order as the variants of
+/// `std.fs.FileType`.
+const FILE_TYPE_FILE: i64 = 0;
+const FILE_TYPE_DIRECTORY: i64 = 1;
+const FILE_TYPE_SYMLINK: i64 = 2;
+const FILE_TYPE_OTHER: i64 = 3;
+
+/// The bits of a file mode that make up its permissions, including the setuid,
+/// setgid and sticky bits.
+const PERMISSION_BITS: u32 = 0o7777;
+
+/// The values used to specify what a seek offset is relative to.
+const SEEK_START: i64 = 0;
+const SEEK_CURRENT: i64 = 1;
+const SEEK_END: i64 = 2;
+
+/// The two ends of a pipe created using `inko_file_pipe()`.
+#[repr(C)]
+pub struct RawPipe {
+    pub reader: *mut File,
+    pub writer: *mut File,
+}
+
+/// The metadata of a path, as produced by `inko_path_metadata()`.
+#[repr(C)]
+pub struct RawMetadata {
+    pub file_type: i64,
+    pub size: i64,
+    pub modified_at: f64,
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_file_drop(file: *mut File) {
     drop(Box::from_raw(file));
@@ -18,11 +66,17 @@ pub unsafe extern "system" fn inko_file_seek(
     process: ProcessPointer,
     file: *mut File,
     offset: i64,
+    whence: i64,
 ) -> InkoResult {
-    let seek = if offset < 0 {
-        SeekFrom::End(offset)
-    } else {
-        SeekFrom::Start(offset as u64)
+    let seek = match whence {
+        SEEK_CURRENT => SeekFrom::Current(offset),
+        SEEK_END => SeekFrom::End(offset),
+        SEEK_START => SeekFrom::Start(offset as u64),
+        _ => {
+            return InkoResult::io_error(io::Error::from_raw_os_error(
+                Errno::INVAL.raw_os_error(),
+            ))
+        }
     };
 
     process
@@ -35,13 +89,53 @@ pub unsafe extern "system" fn inko_file_seek(
 pub unsafe extern "system" fn inko_file_flush(
     process: ProcessPointer,
     file: *mut File,
+    durable: i64,
 ) -> InkoResult {
+    let file = &mut *file;
+
     process
-        .blocking(|| (*file).flush())
+        .blocking(|| {
+            file.flush()?;
+
+            if durable != 0 {
+                file.sync_data()
+            } else {
+                Ok(())
+            }
+        })
         .map(|_| InkoResult::none())
         .unwrap_or_else(InkoResult::io_error)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_truncate(
+    process: ProcessPointer,
+    file: *mut File,
+    size: i64,
+) -> InkoResult {
+    if size < 0 {
+        return InkoResult::io_error(io::Error::from_raw_os_error(
+            Errno::INVAL.raw_os_error(),
+        ));
+    }
+
+    process
+        .blocking(|| (*file).set_len(size as u64))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_duplicate(
+    process: ProcessPointer,
+    file: *mut File,
+) -> InkoResult {
+    process
+        .blocking(|| (*file).try_clone())
+        .map(|file| InkoResult::ok(Box::into_raw(Box::new(file)) as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_file_write_string(
     process: ProcessPointer,
@@ -49,7 +143,11 @@ pub unsafe extern "system" fn inko_file_write_string(
     input: *const InkoString,
 ) -> InkoResult {
     process
-        .blocking(|| (*file).write(InkoString::read(input).as_bytes()))
+        .blocking(|| {
+            let input = InkoString::read(input).as_bytes();
+
+            write_all(&mut *file, input)
+        })
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
@@ -61,7 +159,22 @@ pub unsafe extern "system" fn inko_file_write_bytes(
     input: *mut ByteArray,
 ) -> InkoResult {
     process
-        .blocking(|| (*file).write(&(*input).value))
+        .blocking(|| write_all(&mut *file, &(*input).value))
+        .map(|size| InkoResult::ok(size as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_write_vectored(
+    process: ProcessPointer,
+    file: *mut File,
+    buffers: *const *mut ByteArray,
+    length: i64,
+) -> InkoResult {
+    let slices = io_slices(buffers, length);
+
+    process
+        .blocking(|| retry_interrupted(|| (*file).write_vectored(&slices)))
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
@@ -78,6 +191,18 @@ pub unsafe extern "system" fn inko_file_copy(
         .unwrap_or_else(InkoResult::io_error)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_rename(
+    process: ProcessPointer,
+    from: *const InkoString,
+    to: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::rename(InkoString::read(from), InkoString::read(to)))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_file_size(
     process: ProcessPointer,
@@ -89,6 +214,120 @@ pub unsafe extern "system" fn inko_file_size(
         .unwrap_or_else(InkoResult::io_error)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_symlink_type(
+    process: ProcessPointer,
+    path: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::symlink_metadata(InkoString::read(path)))
+        .map(|meta| InkoResult::ok(file_type_code(meta.file_type()) as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_metadata(
+    process: ProcessPointer,
+    path: *const InkoString,
+    out: *mut RawMetadata,
+) -> InkoResult {
+    process
+        .blocking(|| fs::symlink_metadata(InkoString::read(path)))
+        .and_then(|meta| {
+            (*out).file_type = file_type_code(meta.file_type());
+            (*out).size = meta.len() as i64;
+            (*out).modified_at = system_time_to_timestamp(meta.modified()?);
+            Ok(InkoResult::none())
+        })
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_symlink_size(
+    process: ProcessPointer,
+    path: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::symlink_metadata(InkoString::read(path)))
+        .map(|meta| InkoResult::ok(meta.len() as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_permissions(
+    process: ProcessPointer,
+    path: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::metadata(InkoString::read(path)))
+        .map(|meta| {
+            InkoResult::ok((meta.permissions().mode() & PERMISSION_BITS) as _)
+        })
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_set_permissions(
+    process: ProcessPointer,
+    path: *const InkoString,
+    mode: i64,
+) -> InkoResult {
+    if mode < 0 || mode > PERMISSION_BITS as i64 {
+        return InkoResult::io_error(io::Error::from_raw_os_error(
+            Errno::INVAL.raw_os_error(),
+        ));
+    }
+
+    let perms = fs::Permissions::from_mode(mode as u32);
+
+    process
+        .blocking(|| fs::set_permissions(InkoString::read(path), perms))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_create_symlink(
+    process: ProcessPointer,
+    path: *const InkoString,
+    target: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| symlink(InkoString::read(target), InkoString::read(path)))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_create_hard_link(
+    process: ProcessPointer,
+    path: *const InkoString,
+    target: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| {
+            fs::hard_link(InkoString::read(target), InkoString::read(path))
+        })
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_read_symlink(
+    state: *const State,
+    process: ProcessPointer,
+    path: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::read_link(InkoString::read(path)))
+        .map(|p| {
+            let p = p.to_string_lossy().into_owned();
+
+            InkoResult::ok(InkoString::alloc((*state).string_class, p) as _)
+        })
+        .unwrap_or_else(InkoResult::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_file_remove(
     process: ProcessPointer,
@@ -155,6 +394,26 @@ pub unsafe extern "system" fn inko_path_expand(
         .unwrap_or_else(InkoResult::io_error)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_path_absolute(
+    state: *const State,
+    path: *const InkoString,
+) -> InkoResult {
+    let path = Path::new(InkoString::read(path));
+    let result = if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        env::current_dir().map(|dir| dir.join(path))
+    };
+
+    result
+        .map(|p| p.to_string_lossy().into_owned())
+        .map(|p| {
+            InkoResult::ok(InkoString::alloc((*state).string_class, p) as _)
+        })
+        .unwrap_or_else(InkoResult::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_path_is_file(
     process: ProcessPointer,
@@ -203,17 +462,50 @@ pub unsafe extern "system" fn inko_file_open(
     path: *const InkoString,
     mode: i64,
 ) -> InkoResult {
-    let mut opts = OpenOptions::new();
+    open_file(process, options_for_integer(mode), path)
+        .unwrap_or_else(InkoResult::io_error)
+}
 
-    match mode {
-        0 => opts.read(true), // Read-only
-        1 => opts.write(true).truncate(true).create(true), // Write-only
-        2 => opts.append(true).create(true), // Append-only
-        3 => opts.read(true).write(true).create(true), // Read-write
-        _ => opts.read(true).append(true).create(true), // Read-append
-    };
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_blocking(
+    file: *mut File,
+) -> InkoResult {
+    is_blocking(&*file)
+        .map(|blocking| InkoResult::ok(blocking as i64 as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
 
-    open_file(process, opts, path).unwrap_or_else(InkoResult::io_error)
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_set_blocking(
+    file: *mut File,
+    blocking: i64,
+) -> InkoResult {
+    set_blocking(&*file, blocking != 0)
+        .map(|previous| InkoResult::ok(previous as i64 as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_pipe(out: *mut RawPipe) -> i64 {
+    match cloexec_pipe() {
+        Ok((reader, writer)) => {
+            (*out).reader = Box::into_raw(Box::new(reader));
+            (*out).writer = Box::into_raw(Box::new(writer));
+            0
+        }
+        Err(err) => err.raw_os_error() as i64,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_open_mode_string(
+    process: ProcessPointer,
+    path: *const InkoString,
+    mode: *const InkoString,
+) -> InkoResult {
+    options_for_string(InkoString::read(mode))
+        .and_then(|opts| open_file(process, opts, path))
+        .unwrap_or_else(InkoResult::io_error)
 }
 
 #[no_mangle]
@@ -232,6 +524,75 @@ pub unsafe extern "system" fn inko_file_read(
         .unwrap_or_else(InkoResult::io_error)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_read_all(
+    state: *const State,
+    process: ProcessPointer,
+    path: *const InkoString,
+) -> InkoResult {
+    process
+        .blocking(|| fs::read(InkoString::read(path)))
+        .map(|bytes| {
+            InkoResult::ok(
+                ByteArray::alloc((*state).byte_array_class, bytes) as _
+            )
+        })
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_map(
+    process: ProcessPointer,
+    file: *mut File,
+    offset: i64,
+    size: i64,
+) -> InkoResult {
+    if offset < 0 || size < 0 {
+        return InkoResult::io_error(io::Error::from_raw_os_error(
+            Errno::INVAL.raw_os_error(),
+        ));
+    }
+
+    process
+        .blocking(|| MappedFile::new(&*file, offset as u64, size as usize))
+        .map(InkoResult::ok_boxed)
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_map_drop(map: *mut MappedFile) {
+    drop(Box::from_raw(map));
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_map_size(
+    map: *const MappedFile,
+) -> i64 {
+    (*map).as_slice().len() as i64
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_map_get(
+    map: *const MappedFile,
+    index: i64,
+) -> i64 {
+    *(*map).as_slice().get_unchecked(index as usize) as i64
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_file_map_slice(
+    state: *const State,
+    map: *const MappedFile,
+    start: i64,
+    size: i64,
+) -> *mut ByteArray {
+    let bytes = (*map).as_slice();
+    let start = min(start.max(0) as usize, bytes.len());
+    let end = min(start.saturating_add(size.max(0) as usize), bytes.len());
+
+    ByteArray::alloc((*state).byte_array_class, bytes[start..end].to_vec())
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_directory_create(
     process: ProcessPointer,
@@ -276,6 +637,71 @@ pub unsafe extern "system" fn inko_directory_remove_recursive(
         .unwrap_or_else(InkoResult::io_error)
 }
 
+fn options_for_integer(mode: i64) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+
+    match mode {
+        0 => opts.read(true), // Read-only
+        1 => opts.write(true).truncate(true).create(true), // Write-only
+        2 => opts.append(true).create(true), // Append-only
+        3 => opts.read(true).write(true).create(true), // Read-write
+        // Read-write, truncating existing files
+        5 => opts.read(true).write(true).truncate(true).create(true),
+        // Read-write, failing if the file already exists. These files are
+        // used as temporary files, so only the owner can access them.
+        6 => opts.read(true).write(true).create_new(true).mode(0o600),
+        // Read-write, failing if the file doesn't exist
+        7 => opts.read(true).write(true),
+        _ => opts.read(true).append(true).create(true), // Read-append
+    };
+
+    opts
+}
+
+/// Returns the file mode for a C style mode string, such as "r" or "a+".
+///
+/// The returned value is the mode as accepted by `options_for_integer()`.
+fn mode_for_string(mode: &str) -> Option<i64> {
+    match mode {
+        "r" => Some(0),
+        "w" => Some(1),
+        "a" => Some(2),
+        "r+" => Some(7),
+        "a+" => Some(4),
+        "w+" => Some(5),
+        _ => None,
+    }
+}
+
+/// Returns the options to use for a C style mode string, or an `EINVAL` error
+/// if the mode string isn't supported.
+fn options_for_string(mode: &str) -> Result<OpenOptions, io::Error> {
+    mode_for_string(mode).map(options_for_integer).ok_or_else(|| {
+        io::Error::from_raw_os_error(Errno::INVAL.raw_os_error())
+    })
+}
+
+/// Creates a pipe of which both ends are closed when executing a new program.
+///
+/// Files opened using the standard library don't leak into child processes,
+/// and neither should the ends of a pipe.
+#[cfg(not(target_os = "macos"))]
+fn cloexec_pipe() -> Result<(File, File), Errno> {
+    let (reader, writer) = pipe_with(PipeFlags::CLOEXEC)?;
+
+    Ok((File::from(reader), File::from(writer)))
+}
+
+#[cfg(target_os = "macos")]
+fn cloexec_pipe() -> Result<(File, File), Errno> {
+    // macOS doesn't support pipe2(), so we have to set the flag separately.
+    let (reader, writer) = pipe()?;
+
+    fcntl_setfd(&reader, FdFlags::CLOEXEC)?;
+    fcntl_setfd(&writer, FdFlags::CLOEXEC)?;
+    Ok((File::from(reader), File::from(writer)))
+}
+
 unsafe fn open_file(
     process: ProcessPointer,
     options: OpenOptions,
@@ -286,6 +712,18 @@ unsafe fn open_file(
         .map(|file| InkoResult::ok(Box::into_raw(Box::new(file)) as _))
 }
 
+fn file_type_code(typ: fs::FileType) -> i64 {
+    if typ.is_file() {
+        FILE_TYPE_FILE
+    } else if typ.is_dir() {
+        FILE_TYPE_DIRECTORY
+    } else if typ.is_symlink() {
+        FILE_TYPE_SYMLINK
+    } else {
+        FILE_TYPE_OTHER
+    }
+}
+
 fn system_time_to_timestamp(time: SystemTime) -> f64 {
     let duration = if time < UNIX_EPOCH {
         UNIX_EPOCH.duration_since(time)
@@ -295,3 +733,120 @@ fn system_time_to_timestamp(time: SystemTime) -> f64 {
 
     duration.unwrap().as_secs_f64()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustix::io::{fcntl_getfd, FdFlags};
+    use std::io::Read;
+
+    #[test]
+    fn test_mode_for_string() {
+        assert_eq!(mode_for_string("r"), Some(0));
+        assert_eq!(mode_for_string("w"), Some(1));
+        assert_eq!(mode_for_string("a"), Some(2));
+        assert_eq!(mode_for_string("r+"), Some(7));
+        assert_eq!(mode_for_string("a+"), Some(4));
+        assert_eq!(mode_for_string("w+"), Some(5));
+        assert_eq!(mode_for_string(""), None);
+        assert_eq!(mode_for_string("rw"), None);
+        assert_eq!(mode_for_string("R"), None);
+    }
+
+    #[test]
+    fn test_options_for_mode_strings() {
+        let path = std::env::temp_dir().join("inko-test-options-strings");
+        let open = |mode| options_for_string(mode).unwrap().open(&path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            open("r").map_err(|e| e.kind()).err(),
+            Some(io::ErrorKind::NotFound)
+        );
+        assert_eq!(
+            open("r+").map_err(|e| e.kind()).err(),
+            Some(io::ErrorKind::NotFound)
+        );
+        assert!(!path.exists());
+
+        open("w").unwrap().write_all(b"hello").unwrap();
+        open("a").unwrap().write_all(b" world").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hello world");
+
+        let mut output = String::new();
+
+        open("a+").unwrap().read_to_string(&mut output).unwrap();
+        assert_eq!(output, "hello world");
+
+        output.clear();
+        open("r").unwrap().read_to_string(&mut output).unwrap();
+        assert_eq!(output, "hello world");
+
+        let mut file = open("w+").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"");
+        file.write_all(b"foo").unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        output.clear();
+        file.read_to_string(&mut output).unwrap();
+        assert_eq!(output, "foo");
+        assert!(open("r").unwrap().write_all(b"x").is_err());
+
+        open("r+").unwrap().write_all(b"b").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"boo");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_options_for_invalid_mode_strings() {
+        for mode in ["", "x", "rw", "R"] {
+            assert_eq!(
+                options_for_string(mode).err().and_then(|e| e.raw_os_error()),
+                Some(Errno::INVAL.raw_os_error())
+            );
+        }
+    }
+
+    #[test]
+    fn test_options_for_integer_with_new_files() {
+        let path = std::env::temp_dir().join("inko-test-options-new");
+        let _ = fs::remove_file(&path);
+
+        assert!(options_for_integer(6).open(&path).is_ok());
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(
+            options_for_integer(6).open(&path).map_err(|e| e.kind()).err(),
+            Some(io::ErrorKind::AlreadyExists)
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_pipe() {
+        let mut out = RawPipe {
+            reader: std::ptr::null_mut(),
+            writer: std::ptr::null_mut(),
+        };
+
+        unsafe {
+            assert_eq!(inko_file_pipe(&mut out), 0);
+
+            let mut reader = Box::from_raw(out.reader);
+            let mut writer = Box::from_raw(out.writer);
+            let mut output = String::new();
+
+            assert!(fcntl_getfd(&*reader).unwrap().contains(FdFlags::CLOEXEC));
+            assert!(fcntl_getfd(&*writer).unwrap().contains(FdFlags::CLOEXEC));
+            writer.write_all(b"hello").unwrap();
+            drop(writer);
+            reader.read_to_string(&mut output).unwrap();
+
+            assert_eq!(output, "hello");
+        }
+    }
+}
@@ -1,6 +1,55 @@
-use std::io::{self, Read};
+use crate::mem::ByteArray;
+use rustix::fd::AsFd;
+use rustix::fs::{fcntl_getfl, fcntl_setfl, OFlags};
+use std::io::{self, IoSlice, Read, Write};
+use std::slice;
+
+/// Calls the closure until it produces a result other than an error of kind
+/// `Interrupted`.
+///
+/// System calls may fail with `EINTR` when interrupted by a signal, in which
+/// case the operation should just be retried.
+pub(crate) fn retry_interrupted<T>(
+    mut func: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    loop {
+        match func() {
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            result => return result,
+        }
+    }
+}
+
+/// Writes the entire input to a stream, returning the number of bytes written.
+///
+/// A single write may only write part of the input, such as when writing to a
+/// pipe. Unlike `Write::write_all()`, an error produced after part of the input
+/// is written (e.g. `WouldBlock` for a non-blocking stream) results in the
+/// number of bytes written so far, such that the caller knows which bytes have
+/// yet to be written.
+///
+/// Writes interrupted by a signal are retried automatically.
+pub(crate) fn write_all<T: Write>(
+    stream: &mut T,
+    input: &[u8],
+) -> io::Result<usize> {
+    let mut written = 0;
+
+    while written < input.len() {
+        match retry_interrupted(|| stream.write(&input[written..])) {
+            Ok(0) => break,
+            Ok(size) => written += size,
+            Err(_) if written > 0 => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(written)
+}
 
 /// Reads a number of bytes from a buffer into a Vec.
+///
+/// Reads interrupted by a signal are retried automatically.
 pub(crate) fn read_into<T: Read>(
     stream: &mut T,
     output: &mut Vec<u8>,
@@ -14,3 +63,136 @@ pub(crate) fn read_into<T: Read>(
 
     Ok(read as i64)
 }
+
+/// Returns `true` if the file descriptor is in blocking mode.
+pub(crate) fn is_blocking<Fd: AsFd>(fd: Fd) -> io::Result<bool> {
+    Ok(!fcntl_getfl(fd)?.contains(OFlags::NONBLOCK))
+}
+
+/// Puts the file descriptor in blocking or non-blocking mode, returning the
+/// previous mode.
+///
+/// The mode is part of the file description, and thus shared by all file
+/// descriptors referring to the same description (e.g. those created using
+/// `dup()`).
+pub(crate) fn set_blocking<Fd: AsFd>(
+    fd: Fd,
+    blocking: bool,
+) -> io::Result<bool> {
+    let flags = fcntl_getfl(&fd)?;
+    let new_flags = if blocking {
+        flags - OFlags::NONBLOCK
+    } else {
+        flags | OFlags::NONBLOCK
+    };
+
+    if new_flags != flags {
+        fcntl_setfl(&fd, new_flags)?;
+    }
+
+    Ok(!flags.contains(OFlags::NONBLOCK))
+}
+
+/// Returns a list of `IoSlice` values for an array of byte arrays.
+///
+/// The returned slices borrow the bytes of the byte arrays, so the caller must
+/// ensure the byte arrays outlive the slices.
+pub(crate) unsafe fn io_slices<'a>(
+    buffers: *const *mut ByteArray,
+    length: i64,
+) -> Vec<IoSlice<'a>> {
+    slice::from_raw_parts(buffers, length as usize)
+        .iter()
+        .map(|&buf| IoSlice::new(&(*buf).value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_interrupted() {
+        let mut attempts = 0;
+        let result = retry_interrupted(|| {
+            attempts += 1;
+
+            if attempts < 3 {
+                Err(io::Error::from(io::ErrorKind::Interrupted))
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_retry_interrupted_with_other_error() {
+        let mut attempts = 0;
+        let result: io::Result<()> = retry_interrupted(|| {
+            attempts += 1;
+            Err(io::Error::from(io::ErrorKind::NotFound))
+        });
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(attempts, 1);
+    }
+
+    /// A writer that writes at most three bytes per call, and fails once a
+    /// limit is reached.
+    struct ShortWriter {
+        output: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.output.len() >= self.limit {
+                return Err(io::Error::from(io::ErrorKind::WouldBlock));
+            }
+
+            let size = buf.len().min(3);
+
+            self.output.extend_from_slice(&buf[0..size]);
+            Ok(size)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_all() {
+        let mut writer = ShortWriter { output: Vec::new(), limit: 100 };
+
+        assert_eq!(write_all(&mut writer, b"hello world").unwrap(), 11);
+        assert_eq!(writer.output, b"hello world");
+        assert_eq!(write_all(&mut writer, b"").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_write_all_with_error() {
+        let mut writer = ShortWriter { output: Vec::new(), limit: 6 };
+
+        assert_eq!(write_all(&mut writer, b"hello world").unwrap(), 6);
+        assert_eq!(writer.output, b"hello ");
+        assert_eq!(
+            write_all(&mut writer, b"world").unwrap_err().kind(),
+            io::ErrorKind::WouldBlock
+        );
+    }
+
+    #[test]
+    fn test_set_blocking() {
+        let (reader, _writer) = rustix::pipe::pipe().unwrap();
+
+        assert!(is_blocking(&reader).unwrap());
+        assert!(set_blocking(&reader, false).unwrap());
+        assert!(!is_blocking(&reader).unwrap());
+        assert!(!set_blocking(&reader, false).unwrap());
+        assert!(!set_blocking(&reader, true).unwrap());
+        assert!(is_blocking(&reader).unwrap());
+    }
+}
@@ -3,6 +3,7 @@ use crate::mem::{ByteArray, String as InkoString};
 use crate::network_poller::Interest;
 use crate::process::ProcessPointer;
 use crate::result::{error_to_int, Result};
+use crate::runtime::helpers::io_slices;
 use crate::scheduler::timeouts::Timeout;
 use crate::socket::Socket;
 use crate::state::State;
@@ -130,6 +131,25 @@ pub(crate) unsafe extern "system" fn inko_socket_write_bytes(
     .unwrap_or_else(Result::io_error)
 }
 
+#[no_mangle]
+pub(crate) unsafe extern "system" fn inko_socket_write_vectored(
+    state: *const State,
+    process: ProcessPointer,
+    socket: *mut Socket,
+    buffers: *const *mut ByteArray,
+    length: i64,
+    deadline: i64,
+) -> Result {
+    let state = &*state;
+    let slices = io_slices(buffers, length);
+
+    blocking(state, process, &mut *socket, Interest::Write, deadline, |sock| {
+        sock.write_vectored(&slices)
+    })
+    .map(|v| Result::ok(v as _))
+    .unwrap_or_else(Result::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_socket_read(
     state: *const State,
@@ -1,58 +1,193 @@
 use crate::mem::{ByteArray, String as InkoString};
 use crate::process::ProcessPointer;
 use crate::result::Result as InkoResult;
-use crate::runtime::helpers::read_into;
-use std::io::Write;
-use std::io::{stderr, stdin, stdout};
+use crate::runtime::helpers::{read_into, write_all};
+use crate::state::State;
+use rustix::io::Errno;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, stderr, stdin, stdout, BufRead, Seek, SeekFrom, Write};
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The file descriptors of the standard output streams.
+const STDOUT_FD: RawFd = 1;
+const STDERR_FD: RawFd = 2;
+
+/// The last byte written to each standard output stream when translating
+/// newlines.
+///
+/// A CR and LF may be written using separate writes, so we need to know what
+/// the previous write ended with to not turn the LF into another CRLF sequence.
+static STDOUT_LAST_BYTE: AtomicU8 = AtomicU8::new(0);
+static STDERR_LAST_BYTE: AtomicU8 = AtomicU8::new(0);
+
+/// Translates LF newlines into CRLF sequences.
+///
+/// LF bytes that are already preceded by a CR are left as-is. The `prev`
+/// argument is the byte that came before the input, such as the last byte of
+/// a previous write.
+fn translate_newlines(input: &[u8], mut prev: u8) -> Cow<'_, [u8]> {
+    if !input.contains(&b'\n') {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = Vec::with_capacity(input.len() + 8);
+
+    for &byte in input {
+        if byte == b'\n' && prev != b'\r' {
+            output.push(b'\r');
+        }
+
+        output.push(byte);
+        prev = byte;
+    }
+
+    Cow::Owned(output)
+}
+
+/// Writes the input to a stream, optionally translating newlines.
+///
+/// When translating newlines the entire input is written, and the number of
+/// bytes reported is the size of the input _before_ translation. This ensures
+/// callers can't end up writing a partial CRLF sequence.
+fn write<W: Write>(
+    state: &State,
+    stream: &mut W,
+    last_byte: &AtomicU8,
+    input: &[u8],
+) -> io::Result<usize> {
+    if state.config.translate_newlines {
+        write_translated(stream, last_byte, input)
+    } else {
+        write_all(stream, input)
+    }
+}
+
+/// Writes the input to a stream, translating LF newlines into CRLF sequences.
+///
+/// The `last_byte` argument is the last byte written to the stream, and is
+/// updated after writing the input. Callers should hold the lock of the stream,
+/// such that concurrent writes don't interleave with updating this byte.
+fn write_translated<W: Write>(
+    stream: &mut W,
+    last_byte: &AtomicU8,
+    input: &[u8],
+) -> io::Result<usize> {
+    let prev = last_byte.load(Ordering::Relaxed);
+
+    stream.write_all(&translate_newlines(input, prev))?;
+
+    if let Some(&byte) = input.last() {
+        last_byte.store(byte, Ordering::Relaxed);
+    }
+
+    Ok(input.len())
+}
+
+/// Reads a single line into the buffer, including the trailing newline (if
+/// any), returning the number of bytes read.
+///
+/// Input read past the newline stays in the reader's buffer, so subsequent
+/// reads start right after the line.
+fn read_line<R: BufRead>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+) -> io::Result<usize> {
+    reader.read_until(b'\n', buffer)
+}
+
+/// Truncates (or extends) the file a standard output stream is redirected to.
+///
+/// Any buffered output is flushed before changing the size of the file.
+///
+/// After changing the size, the cursor is moved to the new end of the file, so
+/// subsequent writes don't leave a gap of NULL bytes. If the stream isn't
+/// redirected to a regular file (e.g. it's a terminal or pipe), an ESPIPE error
+/// is returned.
+unsafe fn truncate<W: Write>(
+    stream: &mut W,
+    fd: RawFd,
+    size: i64,
+) -> io::Result<()> {
+    if size < 0 {
+        return Err(Errno::INVAL.into());
+    }
+
+    // The file is wrapped in a ManuallyDrop so we don't close the stream when
+    // we're done.
+    let mut file = ManuallyDrop::new(File::from_raw_fd(fd));
+
+    if !file.metadata()?.is_file() {
+        return Err(Errno::SPIPE.into());
+    }
+
+    stream.flush()?;
+    file.set_len(size as u64)?;
+    file.seek(SeekFrom::Start(size as u64)).map(|_| ())
+}
 
 #[no_mangle]
 pub unsafe extern "system" fn inko_stdout_write_string(
+    state: *const State,
     process: ProcessPointer,
     input: *const InkoString,
 ) -> InkoResult {
     let input = InkoString::read(input).as_bytes();
 
     process
-        .blocking(|| stdout().write(input))
+        .blocking(|| {
+            write(&*state, &mut stdout().lock(), &STDOUT_LAST_BYTE, input)
+        })
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn inko_stdout_write_bytes(
+    state: *const State,
     process: ProcessPointer,
     input: *mut ByteArray,
 ) -> InkoResult {
     let input = &(*input).value;
 
     process
-        .blocking(|| stdout().write(input))
+        .blocking(|| {
+            write(&*state, &mut stdout().lock(), &STDOUT_LAST_BYTE, input)
+        })
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn inko_stderr_write_string(
+    state: *const State,
     process: ProcessPointer,
     input: *const InkoString,
 ) -> InkoResult {
     let input = InkoString::read(input).as_bytes();
 
     process
-        .blocking(|| stderr().write(input))
+        .blocking(|| {
+            write(&*state, &mut stderr().lock(), &STDERR_LAST_BYTE, input)
+        })
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
 
 #[no_mangle]
 pub unsafe extern "system" fn inko_stderr_write_bytes(
+    state: *const State,
     process: ProcessPointer,
     input: *mut ByteArray,
 ) -> InkoResult {
     let input = &(*input).value;
 
     process
-        .blocking(|| stderr().write(input))
+        .blocking(|| {
+            write(&*state, &mut stderr().lock(), &STDERR_LAST_BYTE, input)
+        })
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
@@ -67,6 +202,28 @@ pub unsafe extern "system" fn inko_stderr_flush(process: ProcessPointer) {
     let _ = process.blocking(|| stderr().flush());
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_stdout_truncate(
+    process: ProcessPointer,
+    size: i64,
+) -> InkoResult {
+    process
+        .blocking(|| truncate(&mut stdout(), STDOUT_FD, size))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_stderr_truncate(
+    process: ProcessPointer,
+    size: i64,
+) -> InkoResult {
+    process
+        .blocking(|| truncate(&mut stderr(), STDERR_FD, size))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_stdin_read(
     process: ProcessPointer,
@@ -80,3 +237,90 @@ pub unsafe extern "system" fn inko_stdin_read(
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
 }
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_stdin_read_line(
+    process: ProcessPointer,
+    buffer: *mut ByteArray,
+) -> InkoResult {
+    let buffer = &mut (*buffer).value;
+
+    process
+        .blocking(|| read_line(&mut stdin().lock(), buffer))
+        .map(|size| InkoResult::ok(size as _))
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs;
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn test_truncate() {
+        let path = temp_dir().join("inko_test_stdio_truncate");
+        let mut file = File::create(&path).unwrap();
+
+        file.write_all(b"hello").unwrap();
+
+        unsafe {
+            truncate(&mut io::sink(), file.as_raw_fd(), 2).unwrap();
+            assert!(truncate(&mut io::sink(), file.as_raw_fd(), -1).is_err());
+        }
+
+        file.write_all(b"y").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hey");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut reader = io::Cursor::new(b"a\nb\n".to_vec());
+        let mut buffer = Vec::new();
+
+        assert_eq!(read_line(&mut reader, &mut buffer).unwrap(), 2);
+        assert_eq!(buffer, b"a\n");
+
+        buffer.clear();
+        assert_eq!(read_line(&mut reader, &mut buffer).unwrap(), 2);
+        assert_eq!(buffer, b"b\n");
+
+        buffer.clear();
+        assert_eq!(read_line(&mut reader, &mut buffer).unwrap(), 0);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_translate_newlines() {
+        assert_eq!(translate_newlines(b"foo", 0).as_ref(), b"foo");
+        assert_eq!(translate_newlines(b"foo\n", 0).as_ref(), b"foo\r\n");
+        assert_eq!(translate_newlines(b"\n\n", 0).as_ref(), b"\r\n\r\n");
+        assert_eq!(translate_newlines(b"a\r\nb", 0).as_ref(), b"a\r\nb");
+        assert_eq!(translate_newlines(b"\nb", b'\r').as_ref(), b"\nb");
+        assert_eq!(translate_newlines(b"\nb", b'a').as_ref(), b"\r\nb");
+        assert!(matches!(translate_newlines(b"foo", 0), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_write_translated() {
+        let last_byte = AtomicU8::new(0);
+        let mut output = Vec::new();
+
+        assert_eq!(
+            write_translated(&mut output, &last_byte, b"a\r").unwrap(),
+            2
+        );
+        assert_eq!(write_translated(&mut output, &last_byte, b"").unwrap(), 0);
+        assert_eq!(
+            write_translated(&mut output, &last_byte, b"\nb").unwrap(),
+            2
+        );
+        assert_eq!(
+            write_translated(&mut output, &last_byte, b"\n").unwrap(),
+            1
+        );
+        assert_eq!(output, b"a\r\nb\r\n");
+    }
+}
@@ -8,6 +8,13 @@ use std::slice;
 use std::str;
 use unicode_segmentation::{Graphemes, UnicodeSegmentation};
 
+/// The error codes produced when parsing a number fails.
+///
+/// These values must be kept in sync with `std.int.ParseError`.
+const PARSE_EMPTY: i64 = 0;
+const PARSE_INVALID: i64 = 1;
+const PARSE_OUT_OF_RANGE: i64 = 2;
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_string_new(
     state: *const State,
@@ -102,9 +109,47 @@ pub unsafe extern "system" fn inko_string_to_float(
         _ => slice.parse::<f64>(),
     };
 
-    parsed
-        .map(|v| InkoResult::ok(v.to_bits() as _))
-        .unwrap_or_else(|_| InkoResult::none())
+    parsed.map(|v| InkoResult::ok(v.to_bits() as _)).unwrap_or_else(|_| {
+        let code = if size == 0 { PARSE_EMPTY } else { PARSE_INVALID };
+
+        InkoResult::error(code as _)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_string_to_int(
+    bytes: *mut u8,
+    size: i64,
+    radix: i64,
+) -> InkoResult {
+    let slice = slice::from_raw_parts(bytes, size as _);
+
+    // Input that isn't valid UTF-8 can't consist of only valid digits, so we
+    // don't need to bother with the unchecked conversion used for floats.
+    let Ok(string) = str::from_utf8(slice) else {
+        return InkoResult::error(PARSE_INVALID as _);
+    };
+
+    let radix = radix as u32;
+
+    i64::from_str_radix(string, radix)
+        .map(|v| InkoResult::ok(v as _))
+        .unwrap_or_else(|_| {
+            let digits =
+                string.strip_prefix(|c| c == '+' || c == '-').unwrap_or(string);
+
+            let code = if string.is_empty() {
+                PARSE_EMPTY
+            } else if !digits.is_empty()
+                && digits.chars().all(|c| c.is_digit(radix))
+            {
+                PARSE_OUT_OF_RANGE
+            } else {
+                PARSE_INVALID
+            };
+
+            InkoResult::error(code as _)
+        })
 }
 
 #[no_mangle]
@@ -163,6 +208,37 @@ pub unsafe extern "system" fn inko_string_slice_bytes(
     ByteArray::alloc((*state).byte_array_class, bytes)
 }
 
+#[no_mangle]
+pub unsafe extern "system" fn inko_string_slice_bytes_checked(
+    state: *const State,
+    string: *const InkoString,
+    start: i64,
+    length: i64,
+) -> InkoResult {
+    let string = InkoString::read(string);
+
+    if start < 0 || length < 0 {
+        return InkoResult::none();
+    }
+
+    let start = start as usize;
+    let end = match start.checked_add(length as usize) {
+        Some(end) if end <= string.len() => end,
+        _ => return InkoResult::none(),
+    };
+
+    // `str::get` returns `None` if either end of the range doesn't fall on a
+    // character boundary, which is exactly what we want here.
+    string
+        .get(start..end)
+        .map(|v| {
+            let res = InkoString::alloc((*state).string_class, v.to_string());
+
+            InkoResult::ok(res as _)
+        })
+        .unwrap_or_else(InkoResult::none)
+}
+
 #[no_mangle]
 pub unsafe extern "system" fn inko_string_from_pointer(
     state: *const State,
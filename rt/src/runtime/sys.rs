@@ -1,8 +1,10 @@
 use crate::mem::{ByteArray, String as InkoString};
 use crate::process::ProcessPointer;
 use crate::result::Result as InkoResult;
-use crate::runtime::helpers::read_into;
-use crate::scheduler::number_of_cores;
+use crate::runtime::helpers::{read_into, write_all};
+use crate::state::State;
+use rustix::fs::Mode;
+use rustix::process::umask;
 use std::io::Write;
 use std::process::{Child, Command, Stdio};
 use std::slice;
@@ -137,7 +139,7 @@ pub(crate) unsafe extern "system" fn inko_child_process_stdin_write_bytes(
     child
         .stdin
         .as_mut()
-        .map(|stream| process.blocking(|| stream.write(input)))
+        .map(|stream| process.blocking(|| write_all(stream, input)))
         .unwrap_or(Ok(0))
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
@@ -155,7 +157,7 @@ pub(crate) unsafe extern "system" fn inko_child_process_stdin_write_string(
     child
         .stdin
         .as_mut()
-        .map(|stream| process.blocking(|| stream.write(input.as_bytes())))
+        .map(|stream| process.blocking(|| write_all(stream, input.as_bytes())))
         .unwrap_or(Ok(0))
         .map(|size| InkoResult::ok(size as _))
         .unwrap_or_else(InkoResult::io_error)
@@ -206,6 +208,30 @@ pub(crate) unsafe extern "system" fn inko_child_process_drop(
 }
 
 #[no_mangle]
-pub(crate) unsafe extern "system" fn inko_cpu_cores() -> i64 {
-    number_of_cores() as i64
+pub(crate) unsafe extern "system" fn inko_cpu_cores(
+    state: *const State,
+) -> i64 {
+    (*state).cores as i64
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "system" fn inko_process_threads(
+    state: *const State,
+) -> i64 {
+    (*state).config.process_threads as i64
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "system" fn inko_umask_get() -> i64 {
+    // There's no way of reading the mask without also setting it, so we reset
+    // it immediately after reading it.
+    let mask = umask(Mode::empty());
+
+    umask(mask);
+    mask.bits() as i64
+}
+
+#[no_mangle]
+pub(crate) unsafe extern "system" fn inko_umask_set(mask: i64) -> i64 {
+    umask(Mode::from_bits_truncate(mask as _)).bits() as i64
 }
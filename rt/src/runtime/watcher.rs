@@ -0,0 +1,109 @@
+use crate::context;
+use crate::mem::String as InkoString;
+use crate::process::ProcessPointer;
+use crate::result::{error_to_int, Result as InkoResult};
+use crate::scheduler::timeouts::Timeout;
+use crate::state::State;
+use crate::watcher::{Event, Watcher};
+use std::io;
+use std::path::PathBuf;
+
+/// A file system event produced by `inko_watcher_next()`.
+#[repr(C)]
+pub struct RawEvent {
+    pub path: *const InkoString,
+    pub kind: i64,
+}
+
+fn blocking(
+    state: &State,
+    mut process: ProcessPointer,
+    watcher: &mut Watcher,
+    deadline: i64,
+) -> io::Result<Event> {
+    match watcher.next() {
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        val => return val,
+    }
+
+    let poll_id = unsafe { process.thread() }.network_poller;
+
+    // Just as with sockets, the process' state lock must be held until the
+    // watcher is registered, otherwise a timeout may reschedule the process
+    // before we finish registering it.
+    {
+        let mut proc_state = process.state();
+
+        // A deadline of -1 signals that we should wait indefinitely.
+        if deadline >= 0 {
+            let time = Timeout::until(deadline as u64);
+
+            proc_state.waiting_for_io(Some(time.clone()));
+            state.timeout_worker.suspend(process, time);
+        } else {
+            proc_state.waiting_for_io(None);
+        }
+
+        watcher.register(state, process, poll_id)?;
+    }
+
+    // Safety: the current thread is holding on to the process' run lock, so if
+    // the process gets rescheduled onto a different thread, said thread won't
+    // be able to use it until we finish this context switch.
+    unsafe { context::switch(process) };
+
+    if process.timeout_expired() {
+        watcher.deregister(state);
+        return Err(io::Error::from(io::ErrorKind::TimedOut));
+    }
+
+    watcher.next()
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_watcher_new() -> InkoResult {
+    Watcher::new()
+        .map(InkoResult::ok_boxed)
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_watcher_add(
+    process: ProcessPointer,
+    watcher: *mut Watcher,
+    path: *const InkoString,
+) -> InkoResult {
+    let path = PathBuf::from(InkoString::read(path));
+
+    process
+        .blocking(|| (*watcher).add(path))
+        .map(|_| InkoResult::none())
+        .unwrap_or_else(InkoResult::io_error)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_watcher_next(
+    state: *const State,
+    process: ProcessPointer,
+    watcher: *mut Watcher,
+    deadline: i64,
+    out: *mut RawEvent,
+) -> i64 {
+    let state = &*state;
+
+    match blocking(state, process, &mut *watcher, deadline) {
+        Ok(event) => {
+            let path = event.path.to_string_lossy().into_owned();
+
+            (*out).path = InkoString::alloc(state.string_class, path);
+            (*out).kind = event.kind as i64;
+            0
+        }
+        Err(err) => error_to_int(err),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn inko_watcher_drop(watcher: *mut Watcher) {
+    drop(Box::from_raw(watcher));
+}
@@ -2,6 +2,7 @@ use crate::arc_without_weak::ArcWithoutWeak;
 use crate::config::Config;
 use crate::mem::{ByteArray, Class, ClassPointer, String as InkoString};
 use crate::network_poller::NetworkPoller;
+use crate::scheduler::number_of_cores;
 use crate::scheduler::process::Scheduler;
 use crate::scheduler::timeout_worker::TimeoutWorker;
 use rand::{thread_rng, Rng};
@@ -108,6 +109,13 @@ pub struct State {
 
     /// The network pollers to use for process threads.
     pub(crate) network_pollers: Vec<NetworkPoller>,
+
+    /// The number of CPU cores available to the program.
+    ///
+    /// This value is determined once when the runtime starts, as
+    /// `available_parallelism()` may perform several system calls (e.g. to
+    /// read cgroup quotas).
+    pub(crate) cores: usize,
 }
 
 unsafe impl Sync for State {}
@@ -148,6 +156,7 @@ impl State {
             network_pollers,
             string_class,
             byte_array_class,
+            cores: number_of_cores(),
         };
 
         ArcWithoutWeak::new(state)
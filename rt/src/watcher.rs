@@ -0,0 +1,347 @@
+//! Watching paths for file system changes.
+use crate::process::ProcessPointer;
+use crate::state::State;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicI8;
+
+#[cfg(target_os = "linux")]
+use crate::network_poller::Interest;
+
+#[cfg(target_os = "linux")]
+use rustix::fd::AsFd;
+
+#[cfg(target_os = "linux")]
+use std::ffi::OsStr;
+
+#[cfg(target_os = "linux")]
+use std::os::unix::ffi::OsStrExt;
+
+#[cfg(target_os = "linux")]
+use std::fs::File;
+
+#[cfg(target_os = "linux")]
+use std::sync::atomic::Ordering;
+
+#[cfg(target_os = "linux")]
+use rustix::fs::inotify::{
+    inotify_add_watch, inotify_init, CreateFlags, WatchFlags,
+};
+
+/// The registered value to use to signal a watcher isn't registered with a
+/// network poller.
+#[cfg(target_os = "linux")]
+const NOT_REGISTERED: i8 = -1;
+
+/// The size of the header of an inotify event, excluding the name.
+#[cfg(target_os = "linux")]
+const INOTIFY_HEADER_SIZE: usize = 16;
+
+/// The IN_IGNORED bit set when a watch is removed, such as when the watched
+/// path no longer exists.
+#[cfg(target_os = "linux")]
+const INOTIFY_IGNORED: u32 = 0x8000;
+
+/// The kind of change made to a path, in the same order as the variants of
+/// `std.fs.watcher.EventKind`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum EventKind {
+    Created = 0,
+    Modified = 1,
+    Removed = 2,
+}
+
+/// A change made to a watched path.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Event {
+    pub(crate) path: PathBuf,
+    pub(crate) kind: EventKind,
+}
+
+/// A non-blocking watcher of file system changes that can be registered with a
+/// `NetworkPoller`.
+///
+/// On Linux this uses inotify. Other platforms aren't supported at this time,
+/// and produce an error when creating a watcher.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct Watcher {
+    /// The inotify file descriptor, wrapped in a `File` such that it's closed
+    /// when the watcher is dropped.
+    #[cfg(target_os = "linux")]
+    inner: File,
+
+    /// The paths watched, mapped to their watch descriptors.
+    paths: HashMap<i32, PathBuf>,
+
+    /// Events read but not yet produced.
+    pending: VecDeque<Event>,
+
+    /// The ID of the network poller we're registered with, or -1 if we aren't
+    /// registered with any poller.
+    registered: AtomicI8,
+}
+
+impl Watcher {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new() -> io::Result<Watcher> {
+        let inner = File::from(inotify_init(
+            CreateFlags::CLOEXEC | CreateFlags::NONBLOCK,
+        )?);
+
+        Ok(Watcher {
+            inner,
+            paths: HashMap::new(),
+            pending: VecDeque::new(),
+            registered: AtomicI8::new(NOT_REGISTERED),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn new() -> io::Result<Watcher> {
+        Err(unsupported())
+    }
+
+    /// Starts watching the given path.
+    ///
+    /// For directories this includes changes to the files directly inside the
+    /// directory, but not changes in sub directories.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn add(&mut self, path: PathBuf) -> io::Result<()> {
+        let flags = WatchFlags::CREATE
+            | WatchFlags::MOVED_TO
+            | WatchFlags::MODIFY
+            | WatchFlags::ATTRIB
+            | WatchFlags::DELETE
+            | WatchFlags::DELETE_SELF
+            | WatchFlags::MOVED_FROM
+            | WatchFlags::MOVE_SELF;
+        let id = inotify_add_watch(self.inner.as_fd(), &path, flags)?;
+
+        self.paths.insert(id, path);
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn add(&mut self, _: PathBuf) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    /// Returns the next event, or an error of kind `WouldBlock` if there are
+    /// no events.
+    pub(crate) fn next(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+
+            self.read_events()?;
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_events(&mut self) -> io::Result<()> {
+        let mut buffer = [0_u8; 4096];
+        let size = rustix::io::read(&self.inner, &mut buffer)?;
+
+        self.parse_events(&buffer[0..size]);
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read_events(&mut self) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn parse_events(&mut self, mut bytes: &[u8]) {
+        let int = |bytes: &[u8]| u32::from_ne_bytes(bytes.try_into().unwrap());
+
+        while bytes.len() >= INOTIFY_HEADER_SIZE {
+            let id = int(&bytes[0..4]) as i32;
+            let mask = WatchFlags::from_bits_retain(int(&bytes[4..8]));
+            let name_size = int(&bytes[12..16]) as usize;
+            let end = INOTIFY_HEADER_SIZE + name_size;
+            let name = &bytes[INOTIFY_HEADER_SIZE..end];
+
+            bytes = &bytes[end..];
+
+            if mask.bits() & INOTIFY_IGNORED != 0 {
+                self.paths.remove(&id);
+                continue;
+            }
+
+            let kind = if mask
+                .intersects(WatchFlags::CREATE | WatchFlags::MOVED_TO)
+            {
+                EventKind::Created
+            } else if mask.intersects(WatchFlags::MODIFY | WatchFlags::ATTRIB) {
+                EventKind::Modified
+            } else if mask.intersects(
+                WatchFlags::DELETE
+                    | WatchFlags::DELETE_SELF
+                    | WatchFlags::MOVED_FROM
+                    | WatchFlags::MOVE_SELF,
+            ) {
+                EventKind::Removed
+            } else {
+                continue;
+            };
+
+            let Some(dir) = self.paths.get(&id) else { continue };
+
+            // The name is padded with NULL bytes, and only present for events
+            // about a file in a watched directory.
+            let name = name.split(|&b| b == 0).next().unwrap_or(&[]);
+            let path = if name.is_empty() {
+                dir.clone()
+            } else {
+                dir.join(OsStr::from_bytes(name))
+            };
+
+            self.pending.push_back(Event { path, kind });
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn register(
+        &mut self,
+        state: &State,
+        process: ProcessPointer,
+        thread_poller_id: usize,
+    ) -> io::Result<()> {
+        let existing_id = self.registered.load(Ordering::Acquire);
+
+        // As with sockets, once registered the process may be rescheduled
+        // immediately, so "self" must not be used after adding it to the
+        // poller.
+        if existing_id == NOT_REGISTERED {
+            let poller = &state.network_pollers[thread_poller_id];
+
+            self.registered.store(thread_poller_id as i8, Ordering::Release);
+            poller.add(process, &self.inner, Interest::Read)
+        } else {
+            let poller = &state.network_pollers[existing_id as usize];
+
+            poller.modify(process, &self.inner, Interest::Read)
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn register(
+        &mut self,
+        _: &State,
+        _: ProcessPointer,
+        _: usize,
+    ) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn deregister(&mut self, state: &State) {
+        let poller_id = self.registered.load(Ordering::Acquire) as usize;
+        let _ = state.network_pollers[poller_id].delete(&self.inner);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn deregister(&mut self, _: &State) {}
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::from_raw_os_error(rustix::io::Errno::NOSYS.raw_os_error())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn test_next() {
+        let dir = temp_dir().join("inko-test-watcher-next");
+        let file = dir.join("test.txt");
+        let mut watcher = Watcher::new().unwrap();
+
+        let _ = fs::remove_dir_all(&dir);
+
+        fs::create_dir(&dir).unwrap();
+        watcher.add(dir.clone()).unwrap();
+
+        assert_eq!(
+            watcher.next().map_err(|e| e.kind()),
+            Err(io::ErrorKind::WouldBlock)
+        );
+
+        fs::write(&file, "hello").unwrap();
+        fs::remove_file(&file).unwrap();
+
+        let mut events = Vec::new();
+
+        while let Ok(event) = watcher.next() {
+            events.push(event);
+        }
+
+        assert_eq!(
+            events.first(),
+            Some(&Event { path: file.clone(), kind: EventKind::Created })
+        );
+        assert!(events.contains(&Event {
+            path: file.clone(),
+            kind: EventKind::Modified
+        }));
+        assert_eq!(
+            events.last(),
+            Some(&Event { path: file, kind: EventKind::Removed })
+        );
+
+        fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_next_with_removed_directory() {
+        let dir = temp_dir().join("inko-test-watcher-removed");
+        let mut watcher = Watcher::new().unwrap();
+
+        let _ = fs::remove_dir_all(&dir);
+
+        fs::create_dir(&dir).unwrap();
+        watcher.add(dir.clone()).unwrap();
+        fs::remove_dir(&dir).unwrap();
+
+        while watcher.next().is_ok() {}
+
+        assert!(watcher.paths.is_empty());
+    }
+
+    #[test]
+    fn test_parse_events_with_non_utf8_name() {
+        let dir = temp_dir().join("inko-test-watcher-utf8");
+        let mut watcher = Watcher::new().unwrap();
+        let mut bytes = Vec::new();
+
+        watcher.paths.insert(1, dir.clone());
+        bytes.extend_from_slice(&1_i32.to_ne_bytes());
+        bytes.extend_from_slice(&WatchFlags::CREATE.bits().to_ne_bytes());
+        bytes.extend_from_slice(&0_u32.to_ne_bytes());
+        bytes.extend_from_slice(&4_u32.to_ne_bytes());
+        bytes.extend_from_slice(&[b'a', 0xFF, 0, 0]);
+        watcher.parse_events(&bytes);
+
+        assert_eq!(
+            watcher.next().unwrap(),
+            Event {
+                path: dir.join(OsStr::from_bytes(&[b'a', 0xFF])),
+                kind: EventKind::Created
+            }
+        );
+    }
+
+    #[test]
+    fn test_add_with_missing_path() {
+        let mut watcher = Watcher::new().unwrap();
+
+        assert!(watcher.add(temp_dir().join("inko-test-missing")).is_err());
+    }
+}
@@ -88,6 +88,7 @@ pub const RESULT_OK: &str = "Ok";
 pub const RESULT_ERROR: &str = "Error";
 pub const ARRAY_WITH_CAPACITY: &str = "with_capacity";
 pub const ARRAY_PUSH: &str = "push";
+pub const ARRAY_APPEND: &str = "append";
 pub const ARRAY_INTERNAL_NAME: &str = "$Array";
 
 /// The name of the pseudo field used to deference a pointer.